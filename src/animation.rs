@@ -1,10 +1,10 @@
 use bevy::prelude::*;
 
 use crate::{
-    enemy::{DefaultSpriteIndex, Enemy},
+    enemy::{DefaultSpriteIndex, Dying, Enemy},
     player::{Player, PlayerState},
-    state::GameState,
-    CursorPosition, SPRITE_SHEET_W,
+    state::PlayState,
+    CursorPosition, DEATH_ANIMATION_FRAMES, SPRITE_SHEET_W,
 };
 
 pub struct AnimationPlugin;
@@ -21,9 +21,10 @@ impl Plugin for AnimationPlugin {
                 animate_player,
                 flip_player_sprite_x,
                 animate_enemy,
+                animate_dying_enemies,
                 flip_enemy_sprite_x,
             )
-                .run_if(in_state(GameState::InGame)),
+                .run_if(in_state(PlayState::Running)),
         );
     }
 }
@@ -67,6 +68,26 @@ fn animate_enemy(
     }
 }
 
+fn animate_dying_enemies(
+    mut commands: Commands,
+    mut dying_query: Query<(&mut Dying, &mut Sprite, &AnimationTimer, Entity)>,
+) {
+    for (mut dying, mut sprite, timer, entity) in dying_query.iter_mut() {
+        if !timer.just_finished() {
+            continue;
+        }
+
+        dying.frames = dying.frames.saturating_sub(1);
+        if dying.frames == 0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        sprite
+            .color
+            .set_alpha(dying.frames as f32 / DEATH_ANIMATION_FRAMES as f32);
+    }
+}
+
 fn flip_player_sprite_x(
     cursor_position: Res<CursorPosition>,
     mut player_query: Query<(&mut Sprite, &Transform), With<Player>>,
@@ -0,0 +1,356 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use enemy::{spawn_enemy, Enemy, EnemyArchetypes, EnemyDef};
+use player::Player;
+use projectile::spawn_enemy_projectile;
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::state::PlayState;
+use crate::*;
+
+pub struct BossPlugin;
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct BossDef {
+    pub phases: Vec<BossPhase>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct BossPhase {
+    pub health_threshold: f32,
+    pub movement: BossMovement,
+    #[serde(default)]
+    pub attacks: Vec<BossAttack>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum BossMovement {
+    Chase,
+    Charge {
+        speed: f32,
+        windup: f32,
+        duration: f32,
+    },
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct BossAttack {
+    pub interval: f32,
+    pub pattern: AttackPattern,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub enum AttackPattern {
+    RadialBurst {
+        count: u32,
+        speed: f32,
+        damage: f32,
+        lifetime: f32,
+    },
+    Summon {
+        archetype: String,
+        count: u32,
+        radius: f32,
+    },
+}
+
+#[derive(Component)]
+pub struct Boss {
+    pub name: String,
+    pub max_health: f32,
+    pub phase: usize,
+    attack_timers: Vec<Timer>,
+    charge_direction: Option<Vec2>,
+    charge_timer: Timer,
+}
+
+impl Plugin for BossPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                update_boss_phase,
+                update_boss_transform,
+                handle_boss_attacks,
+            )
+                .run_if(in_state(PlayState::Running)),
+        );
+    }
+}
+
+impl Boss {
+    pub fn new(name: String, max_health: f32, def: &BossDef) -> Self {
+        let mut boss = Self {
+            name,
+            max_health,
+            phase: 0,
+            attack_timers: Vec::new(),
+            charge_direction: None,
+            charge_timer: Timer::default(),
+        };
+        if let Some(phase) = def.phases.first() {
+            boss.enter_phase(0, phase);
+        }
+        boss
+    }
+
+    fn enter_phase(&mut self, index: usize, phase: &BossPhase) {
+        self.phase = index;
+        self.attack_timers = phase
+            .attacks
+            .iter()
+            .map(|attack| Timer::from_seconds(attack.interval, TimerMode::Repeating))
+            .collect();
+        self.charge_direction = None;
+        self.charge_timer = match phase.movement {
+            BossMovement::Charge { windup, .. } => Timer::from_seconds(windup, TimerMode::Once),
+            BossMovement::Chase => Timer::default(),
+        };
+    }
+}
+
+pub fn spawn_boss(
+    commands: &mut Commands,
+    handle: &GlobalTextureAtlas,
+    def: &EnemyDef,
+    boss_def: &BossDef,
+    enemy: Enemy,
+    pos: Vec2,
+) -> Entity {
+    let boss = Boss::new(def.name.clone(), enemy.health, boss_def);
+    let entity = spawn_enemy(commands, handle, def, enemy, pos);
+    commands.entity(entity).insert(boss);
+    entity
+}
+
+fn update_boss_phase(
+    enemy_defs: Res<Assets<EnemyDef>>,
+    mut boss_query: Query<(&Enemy, &mut Boss)>,
+) {
+    for (enemy, mut boss) in boss_query.iter_mut() {
+        let Some(boss_def) = enemy_defs
+            .get(&enemy.archetype)
+            .and_then(|def| def.boss.as_ref())
+        else {
+            continue;
+        };
+
+        let health_fraction = enemy.health / boss.max_health;
+        let Some(phase) = boss_def
+            .phases
+            .iter()
+            .rposition(|phase| health_fraction <= phase.health_threshold)
+        else {
+            continue;
+        };
+        if phase > boss.phase {
+            boss.enter_phase(phase, &boss_def.phases[phase]);
+        }
+    }
+}
+
+fn update_boss_transform(
+    time: Res<Time>,
+    enemy_defs: Res<Assets<EnemyDef>>,
+    player_query: Query<&Transform, With<Player>>,
+    mut boss_query: Query<(&mut Transform, &Enemy, &mut Boss), Without<Player>>,
+) {
+    if player_query.is_empty() || boss_query.is_empty() {
+        return;
+    }
+
+    let player_pos: Vec2 = player_query.single().translation.truncate();
+
+    for (mut transform, enemy, mut boss) in boss_query.iter_mut() {
+        let Some(phase) = enemy_defs
+            .get(&enemy.archetype)
+            .and_then(|def| def.boss.as_ref())
+            .and_then(|boss_def| boss_def.phases.get(boss.phase))
+        else {
+            continue;
+        };
+
+        let dir = (player_pos - transform.translation.truncate()).normalize_or_zero();
+        let velocity = match phase.movement {
+            BossMovement::Chase => dir * enemy.speed,
+            BossMovement::Charge {
+                speed,
+                windup,
+                duration,
+            } => {
+                if boss.charge_timer.tick(time.delta()).finished() {
+                    boss.charge_direction = match boss.charge_direction {
+                        Some(_) => {
+                            boss.charge_timer = Timer::from_seconds(windup, TimerMode::Once);
+                            None
+                        }
+                        None => {
+                            boss.charge_timer = Timer::from_seconds(duration, TimerMode::Once);
+                            Some(dir)
+                        }
+                    };
+                }
+                boss.charge_direction.map_or(Vec2::ZERO, |dir| dir * speed)
+            }
+        };
+
+        transform.translation += velocity.extend(0.0) * time.delta_seconds();
+    }
+}
+
+fn handle_boss_attacks(
+    mut commands: Commands,
+    time: Res<Time>,
+    handle: Res<GlobalTextureAtlas>,
+    archetypes: EnemyArchetypes,
+    enemy_defs: Res<Assets<EnemyDef>>,
+    mut boss_query: Query<(&Transform, &Enemy, &mut Boss, Entity)>,
+) {
+    if boss_query.is_empty() {
+        return;
+    }
+
+    let mut rng = rand::thread_rng();
+    for (transform, enemy, mut boss, boss_entity) in boss_query.iter_mut() {
+        if enemy.health <= 0.0 {
+            continue;
+        }
+        let Some(phase) = enemy_defs
+            .get(&enemy.archetype)
+            .and_then(|def| def.boss.as_ref())
+            .and_then(|boss_def| boss_def.phases.get(boss.phase))
+        else {
+            continue;
+        };
+
+        let pos = transform.translation.truncate();
+        for (timer, attack) in boss.attack_timers.iter_mut().zip(&phase.attacks) {
+            if !timer.tick(time.delta()).just_finished() {
+                continue;
+            }
+
+            match &attack.pattern {
+                AttackPattern::RadialBurst {
+                    count,
+                    speed,
+                    damage,
+                    lifetime,
+                } => {
+                    let step = 2.0 * PI / (*count).max(1) as f32;
+                    let offset = rng.gen_range(0.0..step);
+                    for i in 0..*count {
+                        let direction = Vec2::from_angle(offset + step * i as f32);
+                        spawn_enemy_projectile(
+                            &mut commands,
+                            &handle,
+                            pos,
+                            direction * *speed,
+                            *damage,
+                            *lifetime,
+                            boss_entity,
+                        );
+                    }
+                }
+                AttackPattern::Summon {
+                    archetype,
+                    count,
+                    radius,
+                } => {
+                    let Some((minion, minion_def)) = archetypes.get(archetype) else {
+                        continue;
+                    };
+                    for _ in 0..*count {
+                        let offset = Vec2::from_angle(rng.gen_range(0.0..2.0 * PI))
+                            * rng.gen_range(0.0..*radius);
+                        spawn_enemy(
+                            &mut commands,
+                            &handle,
+                            minion_def,
+                            Enemy::new(minion.clone(), minion_def),
+                            pos + offset,
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::test_utils::world_with_time;
+
+    fn warlord() -> EnemyDef {
+        EnemyDef {
+            health: 100.0,
+            speed: 100.0,
+            boss: Some(BossDef {
+                phases: vec![
+                    BossPhase {
+                        health_threshold: 1.0,
+                        movement: BossMovement::Chase,
+                        attacks: Vec::new(),
+                    },
+                    BossPhase {
+                        health_threshold: 0.5,
+                        movement: BossMovement::Charge {
+                            speed: 900.0,
+                            windup: 1.0,
+                            duration: 0.5,
+                        },
+                        attacks: vec![BossAttack {
+                            interval: 2.0,
+                            pattern: AttackPattern::Summon {
+                                archetype: "runner".to_string(),
+                                count: 1,
+                                radius: 10.0,
+                            },
+                        }],
+                    },
+                ],
+            }),
+            ..default()
+        }
+    }
+
+    #[test]
+    fn boss_changes_phase_below_the_health_threshold() {
+        let def = warlord();
+        let boss = Boss::new(def.name.clone(), def.health, def.boss.as_ref().unwrap());
+        let mut enemy_defs = Assets::<EnemyDef>::default();
+        let enemy = Enemy::new(enemy_defs.add(def.clone()), &def);
+
+        let mut world = world_with_time(Duration::from_millis(100));
+        world.insert_resource(enemy_defs);
+        world.spawn((Player, Transform::from_xyz(500.0, 0.0, 0.0)));
+        let boss = world.spawn((enemy, boss, Transform::default())).id();
+
+        // Chasing in the first phase.
+        world.run_system_once(update_boss_transform);
+        assert!(world.get::<Transform>(boss).unwrap().translation.x > 0.0);
+        assert!(world.get::<Boss>(boss).unwrap().attack_timers.is_empty());
+
+        *world.get_mut::<Transform>(boss).unwrap() = Transform::default();
+        world.get_mut::<Enemy>(boss).unwrap().health = 40.0;
+        world.run_system_once(update_boss_phase);
+
+        let state = world.get::<Boss>(boss).unwrap();
+        assert_eq!(state.phase, 1);
+        assert_eq!(state.attack_timers.len(), 1);
+        assert_eq!(state.attack_timers[0].duration(), Duration::from_secs(2));
+
+        // Winding up the charge instead of walking.
+        world.run_system_once(update_boss_transform);
+        assert_eq!(
+            world.get::<Transform>(boss).unwrap().translation,
+            Vec3::ZERO
+        );
+    }
+}
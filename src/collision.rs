@@ -2,12 +2,16 @@ use bevy::utils::Duration;
 
 use bevy::prelude::*;
 use bevy::time::common_conditions::on_timer;
+use damage::{DamageEvent, DamageKind};
 use enemy::Enemy;
-use gun::Bullet;
+use gun::{Bounce, Bullet, BulletDirection, Explosive, Pierce};
 use kd_tree::{KdPoint, KdTree};
-use player::{Player, PlayerEnemyCollisionEvent};
+use passive::{Blade, DamageAura, OrbitingBlades};
+use player::Player;
+use projectile::EnemyProjectile;
+use stats::{Stat, Stats};
 
-use crate::state::GameState;
+use crate::state::PlayState;
 use crate::*;
 
 pub struct CollisionPlugin;
@@ -15,12 +19,13 @@ pub struct CollisionPlugin;
 #[derive(Component)]
 struct Collidable {
     pos: Vec2,
+    radius: f32,
     entity: Entity,
 }
 #[derive(Resource)]
 pub struct EnemyKdTree {
-    kd_tree: KdTree<[f32; 2]>,
-    collidables: Vec<Collidable>,
+    kd_tree: KdTree<Collidable>,
+    max_radius: f32,
 }
 
 impl Plugin for CollisionPlugin {
@@ -29,11 +34,15 @@ impl Plugin for CollisionPlugin {
             Update,
             (
                 handle_enemy_bullet_collision,
+                handle_bullet_world_bounds_collision,
+                handle_enemy_blade_collision,
+                handle_enemy_aura_collision,
                 handle_enemy_player_collision,
+                handle_enemy_projectile_player_collision,
                 update_enemy_kd_tree
                     .run_if(on_timer(Duration::from_secs_f32(KD_TREE_REFRESH_RATE))),
             )
-                .run_if(in_state(GameState::InGame)),
+                .run_if(in_state(PlayState::Running)),
         );
     }
 }
@@ -42,11 +51,41 @@ impl Default for EnemyKdTree {
     fn default() -> Self {
         Self {
             kd_tree: KdTree::build_by_ordered_float(vec![]),
-            collidables: Vec::new(),
+            max_radius: 0.0,
         }
     }
 }
 
+impl EnemyKdTree {
+    pub fn overlapping(&self, pos: Vec2, radius: f32) -> Vec<(Entity, Vec2)> {
+        self.kd_tree
+            .within_radius(&[pos.x, pos.y], radius + self.max_radius)
+            .into_iter()
+            .filter(|collidable| collidable.pos.distance(pos) <= radius + collidable.radius)
+            .map(|collidable| (collidable.entity, collidable.pos))
+            .collect()
+    }
+
+    pub fn nearest_bodies(&self, pos: Vec2, count: usize) -> Vec<(Entity, Vec2, f32)> {
+        self.kd_tree
+            .nearests(&[pos.x, pos.y], count)
+            .into_iter()
+            .map(|nearest| (nearest.item.entity, nearest.item.pos, nearest.item.radius))
+            .collect()
+    }
+
+    pub fn nearest(&self, pos: Vec2, max_radius: f32) -> Option<(Entity, Vec2)> {
+        self.kd_tree
+            .nearest(&[pos.x, pos.y])
+            .filter(|nearest| nearest.squared_distance <= max_radius * max_radius)
+            .map(|nearest| (nearest.item.entity, nearest.item.pos))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.kd_tree.is_empty()
+    }
+}
+
 impl KdPoint for Collidable {
     type Scalar = f32;
     type Dim = typenum::U2;
@@ -58,66 +97,278 @@ impl KdPoint for Collidable {
     }
 }
 
-fn update_enemy_kd_tree(
+pub fn update_enemy_kd_tree(
     mut tree: ResMut<EnemyKdTree>,
-    enemy_query: Query<(&Transform, Entity), With<Enemy>>,
+    enemy_query: Query<(&Transform, &Enemy, Entity)>,
 ) {
-    let mut positions = Vec::new();
     let mut collidables = Vec::new();
+    let mut max_radius: f32 = 0.0;
 
-    for (t, e) in enemy_query.iter() {
+    for (t, enemy, e) in enemy_query.iter() {
         let pos = t.translation.truncate();
-        positions.push([pos.x, pos.y]);
-        collidables.push(Collidable { pos, entity: e });
+        max_radius = max_radius.max(enemy.hit_radius);
+        collidables.push(Collidable {
+            pos,
+            radius: enemy.hit_radius,
+            entity: e,
+        });
     }
 
-    tree.kd_tree = KdTree::build_by_ordered_float(positions);
-    tree.collidables = collidables;
+    tree.kd_tree = KdTree::build_by_ordered_float(collidables);
+    tree.max_radius = max_radius;
 }
 
 fn handle_enemy_bullet_collision(
-    bullet_query: Query<&Transform, With<Bullet>>,
+    mut commands: Commands,
+    mut bullet_query: Query<(&Transform, &Bullet, &mut Pierce, Option<&Explosive>, Entity)>,
     tree: Res<EnemyKdTree>,
-    mut enemy_query: Query<&mut Enemy>,
+    enemy_query: Query<&Enemy>,
+    mut damage_events: EventWriter<DamageEvent>,
 ) {
-    if bullet_query.is_empty() || tree.collidables.is_empty() {
+    if bullet_query.is_empty() || tree.is_empty() {
         return;
     }
 
-    for b_t in bullet_query.iter() {
-        let pos = b_t.translation;
-        let enemies = tree.kd_tree.within_radius(&[pos.x, pos.y], 50.0);
+    for (b_t, bullet, mut pierce, explosive, bullet_entity) in bullet_query.iter_mut() {
+        let pos = b_t.translation.truncate();
+        let enemies = tree.overlapping(pos, BULLET_HIT_RADIUS);
 
-        for enemy_pos in enemies {
-            if let Some(collidable) = tree
-                .collidables
-                .iter()
-                .find(|c| c.pos == Vec2::new(enemy_pos[0], enemy_pos[1]))
-            {
-                if let Ok(mut enemy) = enemy_query.get_mut(collidable.entity) {
-                    enemy.health -= BULLET_DAMAGE;
-                }
+        for (enemy_entity, _) in enemies {
+            if pierce.hit_enemies.contains(&enemy_entity) {
+                continue;
             }
+            let hit = damage_enemy(
+                &enemy_query,
+                &mut damage_events,
+                DamageEvent {
+                    target: enemy_entity,
+                    source: Some(bullet.gun),
+                    amount: bullet.damage,
+                    kind: DamageKind::Bullet,
+                    position: pos,
+                },
+            );
+            if !hit {
+                continue;
+            }
+            pierce.hit_enemies.push(enemy_entity);
+
+            if let Some(explosive) = explosive {
+                explode(
+                    &tree,
+                    &enemy_query,
+                    &mut damage_events,
+                    pos,
+                    explosive,
+                    bullet.gun,
+                );
+                commands.entity(bullet_entity).despawn();
+                break;
+            }
+
+            if pierce.remaining == 0 {
+                commands.entity(bullet_entity).despawn();
+                break;
+            }
+            pierce.remaining -= 1;
+        }
+    }
+}
+
+fn explode(
+    tree: &EnemyKdTree,
+    enemy_query: &Query<&Enemy>,
+    damage_events: &mut EventWriter<DamageEvent>,
+    pos: Vec2,
+    explosive: &Explosive,
+    gun: Entity,
+) {
+    for (enemy_entity, enemy_pos) in tree.overlapping(pos, explosive.radius) {
+        let Ok(enemy) = enemy_query.get(enemy_entity) else {
+            continue;
+        };
+        // Falls off with the distance to the edge of the body, so large enemies are not spared.
+        let edge_dist = (enemy_pos.distance(pos) - enemy.hit_radius).max(0.0);
+        let falloff = 1.0 - edge_dist / explosive.radius;
+        damage_enemy(
+            enemy_query,
+            damage_events,
+            DamageEvent {
+                target: enemy_entity,
+                source: Some(gun),
+                amount: explosive.damage * falloff.max(0.0),
+                kind: DamageKind::Explosion,
+                position: enemy_pos,
+            },
+        );
+    }
+}
+
+fn handle_enemy_blade_collision(
+    player_query: Query<(&OrbitingBlades, &Stats), With<Player>>,
+    blade_query: Query<(&Transform, &Blade, Entity)>,
+    tree: Res<EnemyKdTree>,
+    enemy_query: Query<&Enemy>,
+    mut damage_events: EventWriter<DamageEvent>,
+) {
+    if player_query.is_empty() || blade_query.is_empty() || tree.is_empty() {
+        return;
+    }
+
+    let (blades, stats) = player_query.single();
+    let damage = blades.damage * stats.get(Stat::PassiveDamage);
+    for (t, blade, blade_entity) in blade_query.iter() {
+        if !blade.hit_timer.just_finished() {
+            continue;
+        }
+
+        for (enemy_entity, enemy_pos) in
+            tree.overlapping(t.translation.truncate(), BLADE_HIT_RADIUS)
+        {
+            damage_enemy(
+                &enemy_query,
+                &mut damage_events,
+                DamageEvent {
+                    target: enemy_entity,
+                    source: Some(blade_entity),
+                    amount: damage,
+                    kind: DamageKind::Blade,
+                    position: enemy_pos,
+                },
+            );
+        }
+    }
+}
+
+fn handle_enemy_aura_collision(
+    player_query: Query<(&Transform, &DamageAura, &Stats, Entity), With<Player>>,
+    tree: Res<EnemyKdTree>,
+    enemy_query: Query<&Enemy>,
+    mut damage_events: EventWriter<DamageEvent>,
+) {
+    if player_query.is_empty() || tree.is_empty() {
+        return;
+    }
+
+    let (t, aura, stats, player_entity) = player_query.single();
+    if !aura.timer.just_finished() {
+        return;
+    }
+
+    let radius = aura.radius * stats.get(Stat::AuraRadius);
+    let damage = aura.damage * stats.get(Stat::PassiveDamage);
+    for (enemy_entity, enemy_pos) in tree.overlapping(t.translation.truncate(), radius) {
+        damage_enemy(
+            &enemy_query,
+            &mut damage_events,
+            DamageEvent {
+                target: enemy_entity,
+                source: Some(player_entity),
+                amount: damage,
+                kind: DamageKind::Aura,
+                position: enemy_pos,
+            },
+        );
+    }
+}
+
+fn damage_enemy(
+    enemy_query: &Query<&Enemy>,
+    damage_events: &mut EventWriter<DamageEvent>,
+    event: DamageEvent,
+) -> bool {
+    if !enemy_query
+        .get(event.target)
+        .is_ok_and(|enemy| enemy.health > 0.0)
+    {
+        return false;
+    }
+
+    damage_events.send(event);
+    true
+}
+
+fn handle_bullet_world_bounds_collision(
+    mut bullet_query: Query<(&Transform, &mut BulletDirection, &mut Bounce), With<Bullet>>,
+) {
+    let bounds = Rect::new(-WORLD_W, -WORLD_H, WORLD_W, WORLD_H);
+
+    for (t, mut dir, mut bounce) in bullet_query.iter_mut() {
+        if bounce.0 == 0 {
+            continue;
+        }
+
+        let pos = t.translation;
+        let hit_x =
+            (pos.x > bounds.max.x && dir.0.x > 0.0) || (pos.x < bounds.min.x && dir.0.x < 0.0);
+        let hit_y =
+            (pos.y > bounds.max.y && dir.0.y > 0.0) || (pos.y < bounds.min.y && dir.0.y < 0.0);
+        if hit_x {
+            dir.0.x = -dir.0.x;
+        }
+        if hit_y {
+            dir.0.y = -dir.0.y;
+        }
+        if hit_x || hit_y {
+            bounce.0 -= 1;
         }
     }
 }
 
 fn handle_enemy_player_collision(
-    player_query: Query<&Transform, With<Player>>,
-    enemy_query: Query<&mut Enemy>,
+    player_query: Query<(&Transform, Entity), With<Player>>,
+    enemy_query: Query<&Enemy>,
     tree: Res<EnemyKdTree>,
-    mut ew: EventWriter<PlayerEnemyCollisionEvent>,
+    mut damage_events: EventWriter<DamageEvent>,
 ) {
     if player_query.is_empty() || enemy_query.is_empty() {
         return;
     }
 
-    let player_pos = player_query.single().translation;
-    let enemies = tree
-        .kd_tree
-        .within_radius(&[player_pos.x, player_pos.y], 50.0);
+    let (player_transform, player_entity) = player_query.single();
+    let player_pos = player_transform.translation.truncate();
+    let enemies = tree.overlapping(player_pos, PLAYER_HIT_RADIUS);
+
+    for (enemy_entity, _) in enemies {
+        let Ok(enemy) = enemy_query.get(enemy_entity) else {
+            continue;
+        };
+        damage_events.send(DamageEvent {
+            target: player_entity,
+            source: Some(enemy_entity),
+            amount: enemy.damage,
+            kind: DamageKind::Contact,
+            position: player_pos,
+        });
+    }
+}
+
+fn handle_enemy_projectile_player_collision(
+    mut commands: Commands,
+    player_query: Query<(&Transform, Entity), With<Player>>,
+    projectile_query: Query<(&Transform, &EnemyProjectile, Entity)>,
+    mut damage_events: EventWriter<DamageEvent>,
+) {
+    if player_query.is_empty() || projectile_query.is_empty() {
+        return;
+    }
+
+    let (player_transform, player_entity) = player_query.single();
+    let player_pos = player_transform.translation.truncate();
+
+    for (t, projectile, projectile_entity) in projectile_query.iter() {
+        let pos = t.translation.truncate();
+        if pos.distance(player_pos) > PLAYER_HIT_RADIUS + ENEMY_PROJECTILE_HIT_RADIUS {
+            continue;
+        }
 
-    for _ in enemies.iter() {
-        ew.send(PlayerEnemyCollisionEvent());
+        damage_events.send(DamageEvent {
+            target: player_entity,
+            source: Some(projectile.source),
+            amount: projectile.damage,
+            kind: DamageKind::Projectile,
+            position: pos,
+        });
+        commands.entity(projectile_entity).despawn();
     }
 }
@@ -1,3 +1,5 @@
+use bevy::color::Color;
+
 // Window
 // const WW: f32 = 1024.0;
 // const WH: f32 = 576.0;
@@ -5,6 +7,16 @@
 // Music
 pub const MUSIC_PATH: &str = "music.ogg";
 
+// Settings
+pub const SETTINGS_DIR: &str = "bevy-top-down-shooter";
+pub const SETTINGS_FILE: &str = "settings.ron";
+pub const RESOLUTIONS: [(u32, u32); 4] = [(1280, 720), (1600, 900), (1920, 1080), (2560, 1440)];
+pub const VOLUME_STEP: f32 = 0.1;
+
+// Input
+pub const GAMEPAD_DEADZONE: f32 = 0.2;
+pub const GAMEPAD_AIM_DISTANCE: f32 = 200.0;
+
 // Sprites
 pub const SPRITE_SHEET_PATH: &str = "assets.png";
 pub const SPRITE_SCALE_FACTOR: f32 = 3.5;
@@ -21,25 +33,87 @@ pub const WORLD_H: f32 = 2880.0;
 //Player
 pub const PLAYER_SPEED: f32 = 250.0;
 pub const PLAYER_HEALTH: f32 = 100.0;
+pub const PLAYER_HIT_RADIUS: f32 = 10.0;
+pub const PLAYER_INVULNERABILITY_SECS: f32 = 0.5;
+pub const PLAYER_KNOCKBACK_SPEED: f32 = 600.0;
+pub const INVULNERABILITY_FLASH_INTERVAL: f32 = 0.08;
+pub const KNOCKBACK_SECS: f32 = 0.15;
+pub const DASH_DISTANCE: f32 = 220.0;
+pub const DASH_DURATION_SECS: f32 = 0.15;
+pub const DASH_COOLDOWN_SECS: f32 = 1.5;
+pub const AFTER_IMAGE_INTERVAL_SECS: f32 = 0.03;
+pub const AFTER_IMAGE_FADE_SECS: f32 = 0.25;
 
 // Gun
 pub const GUN_HEIGHT: f32 = 16.0;
-pub const BULLET_SPAWN_INTERVAL: f32 = 0.1;
-pub const BULLET_SPEED: f32 = 1000.0;
-pub const BULLET_DAMAGE: f32 = 100.0;
-pub const BULLET_TIME_SECS: f32 = 1.0;
-pub const NUM_BULLETS_PER_SHOT: usize = 5;
+pub const BULLET_HIT_RADIUS: f32 = 10.0;
+pub const WEAPONS_PATH: &str = "weapons";
+pub const STARTING_WEAPONS: [&str; 1] = ["blaster"];
+pub const MAX_WEAPON_SLOTS: usize = 9;
+
+// Passive weapons
+pub const BLADE_COUNT: u32 = 2;
+pub const MAX_BLADE_COUNT: u32 = 8;
+pub const BLADE_ORBIT_RADIUS: f32 = 120.0;
+pub const BLADE_ANGULAR_SPEED: f32 = 180.0;
+pub const BLADE_DAMAGE: f32 = 50.0;
+pub const BLADE_HIT_INTERVAL: f32 = 0.2;
+pub const BLADE_HIT_RADIUS: f32 = 20.0;
+pub const BLADE_SPRITE_INDEX: usize = 16;
+pub const AURA_RADIUS: f32 = 150.0;
+pub const AURA_DAMAGE: f32 = 30.0;
+pub const AURA_PULSE_INTERVAL: f32 = 1.0;
+pub const AURA_PULSE_FADE_SECS: f32 = 0.3;
 
 // Enemy
 pub const MAX_NUMBER_ENEMY: usize = 50_000;
-pub const SPAWN_RATE_PER_SECOND: usize = 5_000;
-pub const ENEMY_HEALTH: f32 = 100.0;
-pub const ENEMY_SPAWN_INTERVAL: f32 = 1.0;
-pub const ENEMY_SPEED: f32 = 180.0;
-pub const ENEMY_DAMAGE: f32 = 1.0;
+pub const ENEMIES_PATH: &str = "enemies";
+pub const RANGED_ENEMY_RETREAT_RATIO: f32 = 0.8;
+pub const ENEMY_KNOCKBACK_SPEED: f32 = 400.0;
+pub const DEATH_ANIMATION_FRAMES: u32 = 5;
+pub const SEPARATION_NEIGHBOURS: usize = 8;
+pub const SEPARATION_WEIGHT: f32 = 1.5;
+pub const BOSS_SPAWN_DISTANCE: f32 = 1200.0;
+pub const ENEMY_PROJECTILE_HIT_RADIUS: f32 = 8.0;
+pub const ENEMY_PROJECTILE_SPRITE_INDEX: usize = 16;
+
+// Experience
+pub const XP_FIRST_LEVEL: u32 = 5;
+pub const XP_LEVEL_GROWTH: f32 = 1.3;
+pub const XP_GEM_SCALE: f32 = 0.6;
+pub const UPGRADE_CHOICES: usize = 3;
+
+// Pickups
+pub const PICKUP_MAGNET_RADIUS: f32 = 200.0;
+pub const PICKUP_RADIUS: f32 = 30.0;
+pub const PICKUP_SPEED: f32 = 600.0;
+pub const PICKUP_SPRITE_INDEX: usize = 16;
+pub const LOOT_DROP_CHANCE: f64 = 0.01;
+pub const HEAL_PICKUP_AMOUNT: f32 = 30.0;
+pub const FIRE_RATE_BUFF_SECS: f32 = 8.0;
+pub const FIRE_RATE_BUFF_MULTIPLIER: f32 = 2.0;
+pub const SPEED_BUFF_SECS: f32 = 8.0;
+pub const SPEED_BUFF_MULTIPLIER: f32 = 1.5;
+pub const INVINCIBILITY_BUFF_SECS: f32 = 5.0;
+
+// Waves
+pub const WAVES_PATH: &str = "waves";
+pub const WAVE_SCHEDULE: &str = "default";
 
 // Kd-tree
 pub const KD_TREE_REFRESH_RATE: f32 = 0.2;
 
 // Colors
 pub const BG_COLOR: (u8, u8, u8) = (251, 245, 239);
+pub const AURA_COLOR: Color = Color::srgba(1.0, 0.3, 0.3, 0.3);
+pub const BOSS_HEALTH_BAR_COLOR: Color = Color::srgb(0.7, 0.1, 0.1);
+pub const OVERLAY_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.7);
+pub const BUTTON_COLOR: Color = Color::WHITE;
+pub const BUTTON_FOCUSED_COLOR: Color = Color::srgb(0.8, 0.8, 0.8);
+pub const BUTTON_PRESSED_COLOR: Color = Color::srgb(0.6, 0.6, 0.6);
+pub const XP_GEM_COLOR: Color = Color::srgb(0.2, 0.6, 1.0);
+pub const HEAL_PICKUP_COLOR: Color = Color::srgb(0.2, 0.8, 0.2);
+pub const AMMO_PICKUP_COLOR: Color = Color::srgb(0.9, 0.7, 0.1);
+pub const BUFF_PICKUP_COLOR: Color = Color::srgb(0.7, 0.2, 0.9);
+pub const ENEMY_PROJECTILE_COLOR: Color = Color::srgb(0.8, 0.1, 0.1);
+pub const AFTER_IMAGE_COLOR: Color = Color::srgba(0.4, 0.6, 1.0, 0.5);
@@ -0,0 +1,307 @@
+use bevy::prelude::*;
+
+use crate::dash::Dashing;
+use crate::enemy::{Enemy, EnemyKilledEvent};
+use crate::pickup::{BuffKind, Buffs};
+use crate::player::{Health, Player};
+use crate::state::PlayState;
+use crate::*;
+
+pub struct DamagePlugin;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DamageKind {
+    Bullet,
+    Explosion,
+    Blade,
+    Aura,
+    Contact,
+    Projectile,
+    SelfDestruct,
+}
+
+#[derive(Event, Debug, Clone, Copy)]
+pub struct DamageEvent {
+    pub target: Entity,
+    pub source: Option<Entity>,
+    pub amount: f32,
+    pub kind: DamageKind,
+    pub position: Vec2,
+}
+
+#[derive(Component)]
+pub struct Invulnerable(pub Timer);
+#[derive(Component)]
+pub struct Knockback {
+    pub velocity: Vec2,
+    pub timer: Timer,
+}
+
+type DamageableQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut Health,
+        Has<Player>,
+        Has<Invulnerable>,
+        Has<Dashing>,
+        Option<&'static Buffs>,
+    ),
+    Without<Enemy>,
+>;
+
+impl Plugin for DamagePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<DamageEvent>().add_systems(
+            Update,
+            (apply_damage_events, update_invulnerability, apply_knockback)
+                .run_if(in_state(PlayState::Running)),
+        );
+    }
+}
+
+pub fn apply_damage_events(
+    mut commands: Commands,
+    mut events: EventReader<DamageEvent>,
+    mut killed_events: EventWriter<EnemyKilledEvent>,
+    mut enemy_query: Query<&mut Enemy>,
+    mut health_query: DamageableQuery,
+    transform_query: Query<&Transform>,
+) {
+    // Invulnerability is inserted through commands, so also remember who got hit this frame.
+    let mut hit_this_frame: Vec<Entity> = Vec::new();
+
+    for event in events.read() {
+        if let Ok(mut enemy) = enemy_query.get_mut(event.target) {
+            let was_alive = enemy.health > 0.0;
+            enemy.health -= event.amount;
+            if was_alive && enemy.health <= 0.0 {
+                killed_events.send(EnemyKilledEvent {
+                    archetype: enemy.archetype.clone(),
+                    position: transform_query
+                        .get(event.target)
+                        .map_or(event.position, |t| t.translation.truncate()),
+                    killer: event.source,
+                    kind: event.kind,
+                });
+            }
+            continue;
+        }
+
+        let Ok((mut health, is_player, invulnerable, dashing, buffs)) =
+            health_query.get_mut(event.target)
+        else {
+            continue;
+        };
+        let invincible = buffs.is_some_and(|buffs| buffs.is_active(BuffKind::Invincibility));
+        if invulnerable || invincible || hit_this_frame.contains(&event.target) {
+            continue;
+        }
+        // Dashing goes through enemies, but not through their projectiles or explosions.
+        if dashing && event.kind == DamageKind::Contact {
+            continue;
+        }
+
+        health.0 -= event.amount;
+        if !is_player {
+            continue;
+        }
+        hit_this_frame.push(event.target);
+
+        commands
+            .entity(event.target)
+            .try_insert(Invulnerable(Timer::from_seconds(
+                PLAYER_INVULNERABILITY_SECS,
+                TimerMode::Once,
+            )));
+
+        let Some(source) = event.source else {
+            continue;
+        };
+        let target_pos = transform_query
+            .get(event.target)
+            .map_or(event.position, |t| t.translation.truncate());
+        let source_pos = transform_query
+            .get(source)
+            .map_or(event.position, |t| t.translation.truncate());
+        let direction = (target_pos - source_pos).normalize_or_zero();
+
+        commands.entity(event.target).try_insert(Knockback {
+            velocity: direction * PLAYER_KNOCKBACK_SPEED,
+            timer: Timer::from_seconds(KNOCKBACK_SECS, TimerMode::Once),
+        });
+        if event.kind == DamageKind::Contact && ENEMY_KNOCKBACK_SPEED > 0.0 {
+            if let Some(mut source) = commands.get_entity(source) {
+                source.try_insert(Knockback {
+                    velocity: -direction * ENEMY_KNOCKBACK_SPEED,
+                    timer: Timer::from_seconds(KNOCKBACK_SECS, TimerMode::Once),
+                });
+            }
+        }
+    }
+}
+
+fn update_invulnerability(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(&mut Invulnerable, Option<&mut Sprite>, Entity)>,
+) {
+    for (mut invulnerable, sprite, entity) in query.iter_mut() {
+        let finished = invulnerable.0.tick(time.delta()).finished();
+        if finished {
+            commands.entity(entity).remove::<Invulnerable>();
+        }
+
+        if let Some(mut sprite) = sprite {
+            let flash_on = (invulnerable.0.elapsed_secs() / (2.0 * INVULNERABILITY_FLASH_INTERVAL))
+                .fract()
+                < 0.5;
+            let alpha = if finished || !flash_on { 1.0 } else { 0.3 };
+            sprite.color.set_alpha(alpha);
+        }
+    }
+}
+
+fn apply_knockback(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(&mut Transform, &mut Knockback, Entity)>,
+) {
+    for (mut transform, mut knockback, entity) in query.iter_mut() {
+        knockback.timer.tick(time.delta());
+        let strength = knockback.timer.fraction_remaining();
+        transform.translation += (knockback.velocity * strength * time.delta_seconds()).extend(0.0);
+
+        if knockback.timer.finished() {
+            commands.entity(entity).remove::<Knockback>();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::enemy::EnemyDef;
+    use crate::test_utils::world_with_time;
+
+    fn damage_world() -> World {
+        let mut world = World::new();
+        world.init_resource::<Events<DamageEvent>>();
+        world.init_resource::<Events<EnemyKilledEvent>>();
+        world
+    }
+
+    fn enemy(health: f32) -> Enemy {
+        let def = EnemyDef {
+            health,
+            ..default()
+        };
+        Enemy::new(Handle::default(), &def)
+    }
+
+    fn send_damage(world: &mut World, target: Entity, source: Option<Entity>, amount: f32) {
+        world.send_event(DamageEvent {
+            target,
+            source,
+            amount,
+            kind: DamageKind::Contact,
+            position: Vec2::ZERO,
+        });
+        world.run_system_once(apply_damage_events);
+        world.resource_mut::<Events<DamageEvent>>().clear();
+    }
+
+    #[test]
+    fn damage_lowers_enemy_and_player_health() {
+        let mut world = damage_world();
+        let enemy = world.spawn(enemy(100.0)).id();
+        let player = world.spawn(Health(100.0)).id();
+
+        send_damage(&mut world, enemy, None, 30.0);
+        send_damage(&mut world, player, None, 20.0);
+
+        assert_eq!(world.get::<Enemy>(enemy).unwrap().health, 70.0);
+        assert_eq!(world.get::<Health>(player).unwrap().0, 80.0);
+    }
+
+    #[test]
+    fn self_destruct_counts_as_a_kill() {
+        let mut world = damage_world();
+        let enemy = world.spawn(enemy(40.0)).id();
+
+        world.send_event(DamageEvent {
+            target: enemy,
+            source: Some(enemy),
+            amount: 40.0,
+            kind: DamageKind::SelfDestruct,
+            position: Vec2::ZERO,
+        });
+        world.run_system_once(apply_damage_events);
+
+        assert!(world.get::<Enemy>(enemy).unwrap().health <= 0.0);
+        let events = world.resource::<Events<EnemyKilledEvent>>();
+        let mut reader = events.get_reader();
+        let killed: Vec<_> = reader.read(events).collect();
+        assert_eq!(killed.len(), 1);
+        assert_eq!(killed[0].kind, DamageKind::SelfDestruct);
+    }
+
+    #[test]
+    fn player_is_hit_once_while_invulnerable() {
+        let mut world = damage_world();
+        let player = world.spawn((Player, Health(100.0))).id();
+
+        world.send_event(DamageEvent {
+            target: player,
+            source: None,
+            amount: 10.0,
+            kind: DamageKind::Contact,
+            position: Vec2::ZERO,
+        });
+        send_damage(&mut world, player, None, 10.0);
+        assert_eq!(world.get::<Health>(player).unwrap().0, 90.0);
+        assert!(world.get::<Invulnerable>(player).is_some());
+
+        send_damage(&mut world, player, None, 10.0);
+        assert_eq!(world.get::<Health>(player).unwrap().0, 90.0);
+    }
+
+    #[test]
+    fn player_is_knocked_back_away_from_the_source() {
+        let mut world = damage_world();
+        let enemy = world
+            .spawn((enemy(100.0), Transform::from_xyz(-10.0, 0.0, 0.0)))
+            .id();
+        let player = world
+            .spawn((Player, Health(100.0), Transform::default()))
+            .id();
+
+        send_damage(&mut world, player, Some(enemy), 10.0);
+
+        let knockback = world.get::<Knockback>(player).unwrap();
+        assert!(knockback.velocity.x > 0.0);
+        assert_eq!(knockback.velocity.y, 0.0);
+    }
+
+    #[test]
+    fn knockback_ends_when_its_timer_finishes() {
+        let mut world = world_with_time(Duration::from_secs_f32(KNOCKBACK_SECS));
+        let entity = world
+            .spawn((
+                Transform::default(),
+                Knockback {
+                    velocity: Vec2::X * 100.0,
+                    timer: Timer::from_seconds(KNOCKBACK_SECS, TimerMode::Once),
+                },
+            ))
+            .id();
+
+        world.run_system_once(apply_knockback);
+
+        assert!(world.get::<Knockback>(entity).is_none());
+    }
+}
@@ -0,0 +1,154 @@
+use bevy::prelude::*;
+use player::Player;
+
+use crate::input::{Action, ActionState};
+use crate::state::PlayState;
+use crate::world::GameEntity;
+use crate::*;
+
+pub struct DashPlugin;
+
+#[derive(Component)]
+pub struct Dash {
+    pub cooldown: Timer,
+}
+
+#[derive(Component)]
+pub struct Dashing {
+    pub direction: Vec2,
+    pub timer: Timer,
+    trail_timer: Timer,
+}
+
+#[derive(Component)]
+struct AfterImage(Timer);
+
+impl Plugin for DashPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                handle_dash_input,
+                update_dash,
+                update_dash_cooldown,
+                fade_after_images,
+            )
+                .run_if(in_state(PlayState::Running)),
+        );
+    }
+}
+
+impl Default for Dash {
+    fn default() -> Self {
+        // Ready to dash as soon as the game starts.
+        let mut cooldown = Timer::from_seconds(DASH_COOLDOWN_SECS, TimerMode::Once);
+        cooldown.tick(cooldown.duration());
+        Self { cooldown }
+    }
+}
+
+impl Dash {
+    pub fn progress(&self) -> f32 {
+        self.cooldown.fraction()
+    }
+}
+
+fn handle_dash_input(
+    mut commands: Commands,
+    actions: Res<ActionState>,
+    cursor_pos: Res<CursorPosition>,
+    mut player_query: Query<(&Transform, &mut Dash, Entity), Without<Dashing>>,
+) {
+    if player_query.is_empty() {
+        return;
+    }
+    if !actions.just_pressed(Action::Dash) {
+        return;
+    }
+
+    let (transform, mut dash, entity) = player_query.single_mut();
+    if !dash.cooldown.finished() {
+        return;
+    }
+
+    // Dash where the player is moving, or toward the cursor when standing still.
+    let player_pos = transform.translation.truncate();
+    let mut direction = actions.movement.normalize_or_zero();
+    if direction == Vec2::ZERO {
+        direction = cursor_pos.0.map_or(Vec2::X, |cursor| {
+            (cursor - player_pos).normalize_or(Vec2::X)
+        });
+    }
+
+    dash.cooldown.reset();
+    commands.entity(entity).insert(Dashing {
+        direction,
+        timer: Timer::from_seconds(DASH_DURATION_SECS, TimerMode::Once),
+        trail_timer: Timer::from_seconds(AFTER_IMAGE_INTERVAL_SECS, TimerMode::Repeating),
+    });
+}
+
+fn update_dash(
+    mut commands: Commands,
+    time: Res<Time>,
+    handle: Res<GlobalTextureAtlas>,
+    mut player_query: Query<(&mut Transform, &mut Dashing, &Sprite, &TextureAtlas, Entity)>,
+) {
+    if player_query.is_empty() {
+        return;
+    }
+
+    let (mut transform, mut dashing, sprite, atlas, entity) = player_query.single_mut();
+    let speed = DASH_DISTANCE / DASH_DURATION_SECS;
+    transform.translation += (dashing.direction * speed * time.delta_seconds()).extend(0.0);
+
+    if dashing.trail_timer.tick(time.delta()).just_finished() {
+        commands.spawn((
+            SpriteBundle {
+                transform: Transform::from_translation(
+                    transform.translation.truncate().extend(1.0),
+                )
+                .with_scale(transform.scale),
+                texture: handle.image.clone().unwrap(),
+                sprite: Sprite {
+                    color: AFTER_IMAGE_COLOR,
+                    flip_x: sprite.flip_x,
+                    ..default()
+                },
+                ..default()
+            },
+            atlas.clone(),
+            AfterImage(Timer::from_seconds(AFTER_IMAGE_FADE_SECS, TimerMode::Once)),
+            GameEntity,
+        ));
+    }
+
+    if dashing.timer.tick(time.delta()).finished() {
+        commands.entity(entity).remove::<Dashing>();
+    }
+}
+
+fn update_dash_cooldown(
+    time: Res<Time>,
+    mut player_query: Query<&mut Dash, (With<Player>, Without<Dashing>)>,
+) {
+    for mut dash in player_query.iter_mut() {
+        dash.cooldown.tick(time.delta());
+    }
+}
+
+fn fade_after_images(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut image_query: Query<(&mut AfterImage, &mut Sprite, Entity)>,
+) {
+    for (mut after_image, mut sprite, entity) in image_query.iter_mut() {
+        if after_image.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let alpha = AFTER_IMAGE_COLOR.alpha() * after_image.0.fraction_remaining();
+        sprite.color.set_alpha(alpha);
+    }
+}
@@ -1,137 +1,386 @@
-use std::f32::consts::PI;
-use bevy::utils::Duration;
-
 use animation::AnimationTimer;
+use bevy::ecs::system::SystemParam;
 use bevy::math::vec3;
-use bevy::{prelude::*, time::common_conditions::on_timer};
-use rand::rngs::ThreadRng;
-use rand::Rng;
+use bevy::prelude::*;
+use boss::{Boss, BossDef};
+use collision::EnemyKdTree;
+use damage::{DamageEvent, DamageKind};
+use projectile::spawn_enemy_projectile;
+use serde::Deserialize;
 use world::GameEntity;
 
 use crate::player::Player;
-use crate::state::GameState;
+use crate::state::PlayState;
 use crate::*;
 
 pub struct EnemyPlugin;
 
+#[derive(Asset, TypePath, Deserialize, Debug, Clone, Default)]
+pub struct EnemyDef {
+    pub name: String,
+    pub health: f32,
+    pub speed: f32,
+    pub damage: f32,
+    pub hit_radius: f32,
+    pub sprite_index: usize,
+    #[serde(default = "default_scale")]
+    pub scale: f32,
+    #[serde(default)]
+    pub behaviour: EnemyBehaviour,
+    #[serde(default)]
+    pub attack: Option<RangedAttack>,
+    #[serde(default)]
+    pub experience: u32,
+    pub spawn_weight: f32,
+    #[serde(default)]
+    pub boss: Option<BossDef>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub enum EnemyBehaviour {
+    #[default]
+    Chase,
+    Ranged {
+        distance: f32,
+    },
+    Exploder {
+        trigger_radius: f32,
+        radius: f32,
+        damage: f32,
+    },
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct RangedAttack {
+    pub fire_interval: f32,
+    pub range: f32,
+    pub projectile_speed: f32,
+    pub damage: f32,
+    pub lifetime: f32,
+}
+
 #[derive(Component)]
 pub struct Enemy {
     pub health: f32,
+    pub speed: f32,
+    pub damage: f32,
+    pub hit_radius: f32,
+    pub behaviour: EnemyBehaviour,
+    pub attack: Option<RangedAttack>,
+    pub archetype: Handle<EnemyDef>,
+}
+
+#[derive(SystemParam)]
+pub struct EnemyArchetypes<'w> {
+    library: Res<'w, EnemyLibrary>,
+    defs: Res<'w, Assets<EnemyDef>>,
 }
 
+#[derive(Component)]
+pub struct Dying {
+    pub frames: u32,
+}
+#[derive(Event, Debug, Clone)]
+pub struct EnemyKilledEvent {
+    pub archetype: Handle<EnemyDef>,
+    pub position: Vec2,
+    pub killer: Option<Entity>,
+    pub kind: DamageKind,
+}
+#[derive(Component, Default)]
+pub struct Separation(pub Vec2);
+#[derive(Component)]
+pub struct AttackTimer(pub Timer);
 #[derive(Component)]
 pub struct DefaultSpriteIndex {
     pub index: usize,
 }
 
+type WalkingEnemyQuery<'w, 's> = Query<
+    'w,
+    's,
+    (&'static mut Transform, &'static Enemy, &'static Separation),
+    (Without<Player>, Without<Boss>),
+>;
+
 impl Plugin for EnemyPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
             Update,
             (
-                spawn_enemies.run_if(on_timer(Duration::from_secs_f32(ENEMY_SPAWN_INTERVAL))),
-                despawn_dead_enemies,
+                handle_dead_enemies,
                 update_enemy_transform,
+                // Pushes against the positions the tree was just rebuilt with.
+                update_enemy_separation
+                    .after(collision::update_enemy_kd_tree)
+                    .run_if(resource_changed::<EnemyKdTree>),
+                handle_exploder_enemies.before(damage::apply_damage_events),
+                handle_ranged_enemy_attacks,
             )
-                .run_if(in_state(GameState::InGame)),
-        );
+                .run_if(in_state(PlayState::Running)),
+        )
+        .add_event::<EnemyKilledEvent>()
+        .init_asset::<EnemyDef>()
+        .init_asset_loader::<RonAssetLoader<EnemyDef>>();
+    }
+}
+
+impl DefinitionAsset for EnemyDef {
+    const EXTENSIONS: &'static [&'static str] = &["enemy.ron"];
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+fn default_scale() -> f32 {
+    1.0
+}
+
+impl Enemy {
+    pub fn new(archetype: Handle<EnemyDef>, def: &EnemyDef) -> Self {
+        Self {
+            health: def.health,
+            speed: def.speed,
+            damage: def.damage,
+            hit_radius: def.hit_radius,
+            behaviour: def.behaviour,
+            attack: def.attack,
+            archetype,
+        }
     }
 }
 
-fn despawn_dead_enemies(mut commands: Commands, enemy_query: Query<(&Enemy, Entity), With<Enemy>>) {
+impl EnemyArchetypes<'_> {
+    pub fn iter(&self) -> impl Iterator<Item = (Handle<EnemyDef>, &EnemyDef)> {
+        self.library
+            .assets
+            .iter()
+            .filter_map(|handle| self.defs.get(handle).map(|def| (handle.clone(), def)))
+    }
+
+    pub fn get(&self, name: &str) -> Option<(Handle<EnemyDef>, &EnemyDef)> {
+        self.iter().find(|(_, def)| def.name == name)
+    }
+}
+
+pub fn spawn_enemy(
+    commands: &mut Commands,
+    handle: &GlobalTextureAtlas,
+    def: &EnemyDef,
+    enemy: Enemy,
+    pos: Vec2,
+) -> Entity {
+    let attack_timer = enemy.attack.map(|attack| {
+        AttackTimer(Timer::from_seconds(
+            attack.fire_interval,
+            TimerMode::Repeating,
+        ))
+    });
+    let mut entity = commands.spawn((
+        SpriteBundle {
+            transform: Transform::from_translation(vec3(pos.x, pos.y, 1.0))
+                .with_scale(Vec3::splat(SPRITE_SCALE_FACTOR * def.scale)),
+            texture: handle.image.clone().unwrap(),
+            ..default()
+        },
+        TextureAtlas {
+            layout: handle.layout.clone().unwrap(),
+            index: def.sprite_index,
+        },
+        AnimationTimer(Timer::from_seconds(0.08, TimerMode::Repeating)),
+        enemy,
+        Separation::default(),
+        DefaultSpriteIndex {
+            index: def.sprite_index,
+        },
+        GameEntity,
+    ));
+    if let Some(attack_timer) = attack_timer {
+        entity.insert(attack_timer);
+    }
+    entity.id()
+}
+
+fn handle_dead_enemies(mut commands: Commands, enemy_query: Query<(&Enemy, Entity)>) {
     if enemy_query.is_empty() {
         return;
     }
 
     for (enemy, entity) in enemy_query.iter() {
         if enemy.health <= 0.0 {
-            commands.entity(entity).despawn();
+            commands
+                .entity(entity)
+                .remove::<(Enemy, Boss, Separation, AttackTimer)>()
+                .insert(Dying {
+                    frames: DEATH_ANIMATION_FRAMES,
+                });
         }
     }
 }
 
-fn spawn_enemies(
-    mut commands: Commands,
-    handle: Res<GlobalTextureAtlas>,
+fn update_enemy_transform(
     player_query: Query<&Transform, With<Player>>,
-    enemy_query: Query<&Transform, (With<Enemy>, Without<Player>)>,
+    mut enemy_query: WalkingEnemyQuery,
+    time: Res<Time>,
 ) {
-    if player_query.is_empty() {
+    if player_query.is_empty() || enemy_query.is_empty() {
         return;
     }
 
-    let num_enemies: usize = enemy_query.iter().len();
-    let enemy_spawn_count: usize = (MAX_NUMBER_ENEMY - num_enemies).min(SPAWN_RATE_PER_SECOND);
+    let player_pos: Vec2 = player_query.single().translation.truncate();
 
-    if num_enemies >= MAX_NUMBER_ENEMY {
-        return;
-    }
+    for (mut transform, enemy, separation) in enemy_query.iter_mut() {
+        let offset = player_pos - transform.translation.truncate();
+        let dir = offset.normalize_or_zero();
+        let step = match enemy.behaviour {
+            EnemyBehaviour::Ranged { distance } => {
+                let dist = offset.length();
+                if dist > distance {
+                    dir
+                } else if dist < distance * RANGED_ENEMY_RETREAT_RATIO {
+                    -dir
+                } else {
+                    Vec2::ZERO
+                }
+            }
+            EnemyBehaviour::Chase | EnemyBehaviour::Exploder { .. } => dir,
+        };
 
-    let player_pos: Vec2 = player_query.single().translation.truncate();
-    let mut rng: ThreadRng = rand::thread_rng();
-    let enemies_index = [8, 12, 20, 28];
-    for _ in 0..enemy_spawn_count {
-        let random_position_around_player = get_random_position_around(player_pos);
-        let x = random_position_around_player.0;
-        let y = random_position_around_player.1;
-        commands.spawn((
-            SpriteBundle {
-                transform: Transform::from_translation(vec3(x, y, 1.0))
-                    .with_scale(Vec3::splat(SPRITE_SCALE_FACTOR))
-                    .with_scale(Vec3::splat(SPRITE_SCALE_FACTOR)),
-                texture: handle.image.clone().unwrap(),
-                ..default()
-            },
-            TextureAtlas {
-                layout: handle.layout.clone().unwrap(),
-                index: 0,
-            },
-            AnimationTimer(Timer::from_seconds(0.08, TimerMode::Repeating)),
-            Enemy::default(),
-            DefaultSpriteIndex {
-                index: enemies_index[rng.gen_range(0..=3)],
-            },
-            GameEntity,
-        ));
+        let step = step + separation.0 * SEPARATION_WEIGHT;
+        transform.translation += step.extend(0.0) * enemy.speed * time.delta_seconds();
     }
 }
 
-fn get_random_position_around(pos: Vec2) -> (f32, f32) {
-    let mut rng: ThreadRng = rand::thread_rng();
-    let angle = rng.gen_range(0.0..PI * 2.0);
-    let dist = rng.gen_range(2048.0..4096.0);
+fn update_enemy_separation(
+    tree: Res<EnemyKdTree>,
+    mut enemy_query: Query<(&Transform, &Enemy, &mut Separation, Entity)>,
+) {
+    if tree.is_empty() {
+        return;
+    }
+
+    // Only the closest few neighbours are looked at, keeping the cost linear in the enemy count.
+    enemy_query
+        .par_iter_mut()
+        .for_each(|(transform, enemy, mut separation, entity)| {
+            let pos = transform.translation.truncate();
+            let mut push = Vec2::ZERO;
 
-    let offset_x = dist * angle.cos();
-    let offset_y = dist * angle.sin();
+            for (other, other_pos, other_radius) in
+                tree.nearest_bodies(pos, SEPARATION_NEIGHBOURS + 1)
+            {
+                if other == entity {
+                    continue;
+                }
+                let min_dist = enemy.hit_radius + other_radius;
+                let offset = pos - other_pos;
+                let dist = offset.length();
+                if dist >= min_dist {
+                    continue;
+                }
 
-    let random_x = pos.x + offset_x;
-    let random_y = pos.y + offset_y;
+                // Stacked enemies get a direction of their own so they can still split up.
+                let away = if dist > f32::EPSILON {
+                    offset / dist
+                } else {
+                    Vec2::from_angle(entity.index() as f32)
+                };
+                push += away * (1.0 - dist / min_dist);
+            }
 
-    return (random_x, random_y);
+            separation.0 = push.clamp_length_max(1.0);
+        });
 }
 
-impl Default for Enemy {
-    fn default() -> Self {
-        Self {
-            health: ENEMY_HEALTH,
+fn handle_exploder_enemies(
+    player_query: Query<(&Transform, Entity), With<Player>>,
+    enemy_query: Query<(&Transform, &Enemy, Entity), Without<Player>>,
+    mut damage_events: EventWriter<DamageEvent>,
+) {
+    if player_query.is_empty() || enemy_query.is_empty() {
+        return;
+    }
+
+    let (player_transform, player_entity) = player_query.single();
+    let player_pos = player_transform.translation.truncate();
+
+    for (transform, enemy, enemy_entity) in enemy_query.iter() {
+        let EnemyBehaviour::Exploder {
+            trigger_radius,
+            radius,
+            damage,
+        } = enemy.behaviour
+        else {
+            continue;
+        };
+        if enemy.health <= 0.0 {
+            continue;
         }
+
+        let pos = transform.translation.truncate();
+        let dist = pos.distance(player_pos);
+        if dist > trigger_radius {
+            continue;
+        }
+
+        if dist <= radius {
+            damage_events.send(DamageEvent {
+                target: player_entity,
+                source: Some(enemy_entity),
+                amount: damage,
+                kind: DamageKind::Explosion,
+                position: pos,
+            });
+        }
+        damage_events.send(DamageEvent {
+            target: enemy_entity,
+            source: Some(enemy_entity),
+            amount: enemy.health,
+            kind: DamageKind::SelfDestruct,
+            position: pos,
+        });
     }
 }
 
-fn update_enemy_transform(
-    player_query: Query<&Transform, With<Player>>,
-    mut enemy_query: Query<&mut Transform, (With<Enemy>, Without<Player>)>,
+fn handle_ranged_enemy_attacks(
+    mut commands: Commands,
     time: Res<Time>,
+    handle: Res<GlobalTextureAtlas>,
+    player_query: Query<&Transform, With<Player>>,
+    mut enemy_query: Query<(&Transform, &Enemy, &mut AttackTimer, Entity), Without<Player>>,
 ) {
     if player_query.is_empty() || enemy_query.is_empty() {
         return;
     }
 
-    let player_pos: Vec2 = player_query.single().translation.truncate();
+    let player_pos = player_query.single().translation.truncate();
 
-    for mut transform in enemy_query.iter_mut() {
-        let dir: Vec3 = (player_pos.extend(0.0) - transform.translation).normalize();
+    for (transform, enemy, mut attack_timer, enemy_entity) in enemy_query.iter_mut() {
+        if !attack_timer.0.tick(time.delta()).just_finished() {
+            continue;
+        }
+        let Some(attack) = enemy.attack else {
+            continue;
+        };
+        if enemy.health <= 0.0 {
+            continue;
+        }
+
+        let pos = transform.translation.truncate();
+        let offset = player_pos - pos;
+        if offset.length() > attack.range {
+            continue;
+        }
 
-        transform.translation += dir * ENEMY_SPEED * time.delta_seconds();
+        spawn_enemy_projectile(
+            &mut commands,
+            &handle,
+            pos,
+            offset.normalize_or_zero() * attack.projectile_speed,
+            attack.damage,
+            attack.lifetime,
+            enemy_entity,
+        );
     }
 }
@@ -0,0 +1,99 @@
+use bevy::prelude::*;
+use enemy::{EnemyDef, EnemyKilledEvent};
+use pickup::{spawn_pickup, PickupCollectedEvent, PickupKind};
+use player::Player;
+
+use crate::state::PlayState;
+use crate::*;
+
+pub struct ExperiencePlugin;
+
+#[derive(Component)]
+pub struct Experience {
+    pub level: u32,
+    pub current: u32,
+    pub next_level: u32,
+}
+
+impl Plugin for ExperiencePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (spawn_experience_gems, collect_experience, handle_level_up)
+                .run_if(in_state(PlayState::Running)),
+        );
+    }
+}
+
+impl Default for Experience {
+    fn default() -> Self {
+        Self {
+            level: 1,
+            current: 0,
+            next_level: XP_FIRST_LEVEL,
+        }
+    }
+}
+
+impl Experience {
+    pub fn progress(&self) -> f32 {
+        self.current as f32 / self.next_level.max(1) as f32
+    }
+}
+
+fn spawn_experience_gems(
+    mut commands: Commands,
+    handle: Res<GlobalTextureAtlas>,
+    enemy_defs: Res<Assets<EnemyDef>>,
+    mut killed_events: EventReader<EnemyKilledEvent>,
+) {
+    for event in killed_events.read() {
+        let Some(def) = enemy_defs.get(&event.archetype) else {
+            continue;
+        };
+        if def.experience == 0 {
+            continue;
+        }
+
+        spawn_pickup(
+            &mut commands,
+            &handle,
+            PickupKind::Experience(def.experience),
+            event.position,
+        );
+    }
+}
+
+fn collect_experience(
+    mut collected_events: EventReader<PickupCollectedEvent>,
+    mut player_query: Query<&mut Experience, With<Player>>,
+) {
+    for event in collected_events.read() {
+        let PickupKind::Experience(amount) = event.kind else {
+            continue;
+        };
+        if let Ok(mut experience) = player_query.get_mut(event.collector) {
+            experience.current += amount;
+        }
+    }
+}
+
+fn handle_level_up(
+    mut player_query: Query<&mut Experience, With<Player>>,
+    mut next_state: ResMut<NextState<PlayState>>,
+) {
+    if player_query.is_empty() {
+        return;
+    }
+
+    let mut experience = player_query.single_mut();
+    if experience.current < experience.next_level {
+        return;
+    }
+
+    // Any experience left over still counts, the next level up happens once this one is chosen.
+    experience.current -= experience.next_level;
+    experience.level += 1;
+    experience.next_level = (experience.next_level as f32 * XP_LEVEL_GROWTH).ceil() as u32;
+    next_state.set(PlayState::LevelUp);
+}
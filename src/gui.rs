@@ -1,12 +1,26 @@
 use bevy::{
     diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin},
     prelude::*,
+    window::WindowMode,
 };
 
 use crate::{
+    boss::Boss,
+    constants::{BOSS_HEALTH_BAR_COLOR, BUTTON_COLOR, OVERLAY_COLOR, XP_GEM_COLOR},
+    dash::Dash,
     enemy::Enemy,
+    experience::Experience,
+    gun::{Ammo, Reloading, WeaponInventory},
+    input::{Action, InputBinding},
+    menu::{spawn_menu_button, spawn_menu_screen, BackButton, MenuButtonPressed},
+    pickup::{BuffKind, Buffs},
     player::{Health, Player},
-    state::GameState,
+    settings::{PendingRebind, Settings, VolumeChannel},
+    state::{GameState, MainMenuScreen, PauseMenu, PlayState},
+    summary::RunSummary,
+    upgrade::{draw_upgrade_choices, UpgradeChoices, UpgradeChosenEvent},
+    wave::{WaveDirector, WavePhase},
+    weapon::{Weapon, WeaponDef},
     world::GameEntity,
 };
 
@@ -16,21 +30,122 @@ pub struct GuiPlugin;
 struct DebugText;
 #[derive(Component)]
 struct MainMenuItem;
+#[derive(Component)]
+struct AmmoText;
+#[derive(Component)]
+struct ReloadBar;
+#[derive(Component)]
+struct DashBar;
+#[derive(Component)]
+struct WaveText;
+#[derive(Component)]
+struct BossHud;
+#[derive(Component)]
+struct BossNameText;
+#[derive(Component)]
+struct BossHealthBar;
+#[derive(Component)]
+struct LevelText;
+#[derive(Component)]
+struct ExperienceBar;
+#[derive(Component)]
+struct BuffText;
+#[derive(Component)]
+struct LevelUpMenuItem;
+#[derive(Component)]
+struct LevelUpButton(usize);
+#[derive(Component)]
+struct PauseMenuItem;
+#[derive(Component)]
+struct GameOverMenuItem;
+#[derive(Component)]
+struct SettingsMenuItem;
+#[derive(Component)]
+struct ControlsMenuItem;
+#[derive(Component)]
+struct CreditsMenuItem;
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+enum MenuAction {
+    Play,
+    Settings,
+    Credits,
+    Quit,
+    Back,
+    Resume,
+    MainMenu,
+    Retry,
+    CycleWindowMode,
+    CycleResolution,
+    ToggleVsync,
+    StepVolume(VolumeChannel),
+    Controls,
+    Rebind(Action),
+}
 
 impl Plugin for GuiPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(FrameTimeDiagnosticsPlugin)
-            .add_systems(OnEnter(GameState::MainMenu), setup_main_menu)
-            .add_systems(OnExit(GameState::MainMenu), despawn_main_menu)
+            .add_systems(OnEnter(MainMenuScreen::Main), setup_main_menu)
+            .add_systems(OnExit(MainMenuScreen::Main), despawn_main_menu)
+            .add_systems(OnEnter(MainMenuScreen::Credits), setup_credits_menu)
+            .add_systems(OnExit(MainMenuScreen::Credits), despawn_credits_menu)
+            .add_systems(
+                Update,
+                (
+                    handle_menu_actions,
+                    handle_menu_page_actions,
+                    handle_settings_actions,
+                    update_settings_labels.run_if(
+                        resource_changed::<Settings>.or_else(resource_changed::<PendingRebind>),
+                    ),
+                ),
+            )
+            .add_systems(
+                OnEnter(GameState::GameInit),
+                (
+                    spawn_debug_text,
+                    spawn_weapon_hud,
+                    spawn_dash_hud,
+                    spawn_wave_text,
+                    spawn_boss_hud,
+                    spawn_experience_hud,
+                    spawn_buff_text,
+                ),
+            )
             .add_systems(
                 Update,
-                handle_main_menu_buttons.run_if(in_state(GameState::MainMenu)),
+                (
+                    update_debug_text,
+                    update_weapon_hud,
+                    update_dash_hud,
+                    update_wave_text,
+                    update_boss_hud,
+                    update_experience_hud,
+                    update_buff_text,
+                )
+                    .run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(
+                OnEnter(PlayState::LevelUp),
+                setup_level_up_menu.after(draw_upgrade_choices),
             )
-            .add_systems(OnEnter(GameState::GameInit), spawn_debug_text)
+            .add_systems(OnExit(PlayState::LevelUp), despawn_level_up_menu)
             .add_systems(
                 Update,
-                (update_debug_text).run_if(in_state(GameState::InGame)),
-            );
+                handle_level_up_buttons.run_if(in_state(PlayState::LevelUp)),
+            )
+            .add_systems(OnEnter(PauseMenu::Main), setup_pause_menu)
+            .add_systems(OnExit(PauseMenu::Main), despawn_pause_menu)
+            .add_systems(OnEnter(GameState::GameOver), setup_game_over_menu)
+            .add_systems(OnExit(GameState::GameOver), despawn_game_over_menu)
+            .add_systems(OnEnter(MainMenuScreen::Settings), setup_settings_menu)
+            .add_systems(OnExit(MainMenuScreen::Settings), despawn_settings_menu)
+            .add_systems(OnEnter(PauseMenu::Settings), setup_settings_menu)
+            .add_systems(OnExit(PauseMenu::Settings), despawn_settings_menu)
+            .add_systems(OnEnter(MainMenuScreen::Controls), setup_controls_menu)
+            .add_systems(OnExit(MainMenuScreen::Controls), despawn_controls_menu)
+            .add_systems(OnEnter(PauseMenu::Controls), setup_controls_menu)
+            .add_systems(OnExit(PauseMenu::Controls), despawn_controls_menu);
     }
 }
 
@@ -41,58 +156,110 @@ fn despawn_main_menu(mut commands: Commands, menu_item_query: Query<Entity, With
 }
 
 fn setup_main_menu(mut commands: Commands) {
+    let menu = spawn_menu_screen(&mut commands, "Top Down Shooter");
     commands
-        .spawn(NodeBundle {
-            style: Style {
-                width: Val::Percent(100.0),
-                height: Val::Percent(100.0),
-                align_items: AlignItems::Center,
-                justify_content: JustifyContent::Center,
-                ..default()
-            },
-            ..default()
-        })
+        .entity(menu)
+        .insert(MainMenuItem)
         .with_children(|parent| {
-            parent
-                .spawn(ButtonBundle {
-                    style: Style {
-                        width: Val::Px(150.0),
-                        height: Val::Px(65.0),
-                        border: UiRect::all(Val::Px(5.0)),
-                        justify_content: JustifyContent::Center,
-                        align_items: AlignItems::Center,
-                        ..default()
+            spawn_menu_button(parent, "Play", MenuAction::Play);
+            spawn_menu_button(parent, "Settings", MenuAction::Settings);
+            spawn_menu_button(parent, "Credits", MenuAction::Credits);
+            spawn_menu_button(parent, "Quit", MenuAction::Quit);
+        });
+}
+
+fn despawn_credits_menu(
+    mut commands: Commands,
+    menu_item_query: Query<Entity, With<CreditsMenuItem>>,
+) {
+    for e in menu_item_query.iter() {
+        commands.entity(e).despawn_recursive();
+    }
+}
+
+fn setup_credits_menu(mut commands: Commands) {
+    let menu = spawn_menu_screen(&mut commands, "Credits");
+    commands
+        .entity(menu)
+        .insert(CreditsMenuItem)
+        .with_children(|parent| {
+            parent.spawn(
+                TextBundle::from_section(
+                    "Top down shooter made with Bevy",
+                    TextStyle {
+                        font_size: 40.0,
+                        color: Color::BLACK,
+                        ..Default::default()
                     },
-                    border_color: BorderColor(Color::BLACK),
-                    border_radius: BorderRadius::MAX,
-                    ..default()
-                })
-                .with_children(|parent| {
-                    parent.spawn(TextBundle::from_section(
-                        "Play",
-                        TextStyle {
-                            font_size: 40.0,
-                            color: Color::BLACK,
-                            ..Default::default()
-                        },
-                    ));
-                });
-        })
-        .insert(MainMenuItem);
+                )
+                .with_text_justify(JustifyText::Center),
+            );
+            spawn_menu_button(parent, "Back", (MenuAction::Back, BackButton));
+        });
 }
 
-fn handle_main_menu_buttons(
-    mut interaction_query: Query<&Interaction, (Changed<Interaction>, With<Button>)>,
-    mut next_state: ResMut<NextState<GameState>>,
+// Starts, leaves and quits runs.
+fn handle_menu_actions(
+    mut pressed_events: EventReader<MenuButtonPressed>,
+    action_query: Query<&MenuAction>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    mut next_play_state: ResMut<NextState<PlayState>>,
+    mut exit_events: EventWriter<AppExit>,
 ) {
-    for interaction in &mut interaction_query {
-        match interaction {
-            Interaction::Pressed => next_state.set(GameState::GameInit),
+    for event in pressed_events.read() {
+        let Ok(action) = action_query.get(event.0) else {
+            continue;
+        };
+
+        match action {
+            MenuAction::Play | MenuAction::Retry => next_game_state.set(GameState::GameInit),
+            MenuAction::Resume => next_play_state.set(PlayState::Running),
+            MenuAction::MainMenu => next_game_state.set(GameState::MainMenu),
+            MenuAction::Quit => {
+                exit_events.send(AppExit::Success);
+            }
             _ => (),
         }
     }
 }
 
+// Moves between the pages of whichever menu is open, the pause menu or the main menu.
+fn handle_menu_page_actions(
+    mut pressed_events: EventReader<MenuButtonPressed>,
+    action_query: Query<&MenuAction>,
+    main_menu: Option<Res<State<MainMenuScreen>>>,
+    pause_menu: Option<Res<State<PauseMenu>>>,
+    mut next_main_menu: ResMut<NextState<MainMenuScreen>>,
+    mut next_pause_menu: ResMut<NextState<PauseMenu>>,
+) {
+    for event in pressed_events.read() {
+        let Ok(action) = action_query.get(event.0) else {
+            continue;
+        };
+
+        if let Some(pause_menu) = &pause_menu {
+            match (action, pause_menu.get()) {
+                (MenuAction::Settings, _) => next_pause_menu.set(PauseMenu::Settings),
+                (MenuAction::Controls, _) => next_pause_menu.set(PauseMenu::Controls),
+                (MenuAction::Back, PauseMenu::Controls) => next_pause_menu.set(PauseMenu::Settings),
+                (MenuAction::Back, _) => next_pause_menu.set(PauseMenu::Main),
+                _ => (),
+            }
+        } else if let Some(main_menu) = &main_menu {
+            match (action, main_menu.get()) {
+                (MenuAction::Settings, _) => next_main_menu.set(MainMenuScreen::Settings),
+                (MenuAction::Controls, _) => next_main_menu.set(MainMenuScreen::Controls),
+                (MenuAction::Credits, _) => next_main_menu.set(MainMenuScreen::Credits),
+                (MenuAction::Back, MainMenuScreen::Controls) => {
+                    next_main_menu.set(MainMenuScreen::Settings)
+                }
+                (MenuAction::Back, _) => next_main_menu.set(MainMenuScreen::Main),
+                _ => (),
+            }
+        }
+    }
+}
+
 fn spawn_debug_text(mut commands: Commands) {
     commands.spawn((
         TextBundle::from_section(
@@ -127,3 +294,733 @@ fn update_debug_text(
         }
     }
 }
+
+fn spawn_weapon_hud(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    right: Val::Px(20.0),
+                    bottom: Val::Px(20.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::FlexEnd,
+                    row_gap: Val::Px(8.0),
+                    ..default()
+                },
+                ..default()
+            },
+            GameEntity,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 40.0,
+                        color: Color::BLACK,
+                        ..Default::default()
+                    },
+                )
+                .with_text_justify(JustifyText::Right),
+                AmmoText,
+            ));
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Px(200.0),
+                        height: Val::Px(12.0),
+                        border: UiRect::all(Val::Px(2.0)),
+                        ..default()
+                    },
+                    border_color: BorderColor(Color::BLACK),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn((
+                        NodeBundle {
+                            style: Style {
+                                width: Val::Percent(0.0),
+                                height: Val::Percent(100.0),
+                                ..default()
+                            },
+                            background_color: BackgroundColor(Color::BLACK),
+                            ..default()
+                        },
+                        ReloadBar,
+                    ));
+                });
+        });
+}
+
+fn update_weapon_hud(
+    player_query: Query<&WeaponInventory, With<Player>>,
+    gun_query: Query<(&Weapon, &Ammo, Option<&Reloading>)>,
+    weapon_defs: Res<Assets<WeaponDef>>,
+    mut text_query: Query<&mut Text, With<AmmoText>>,
+    mut bar_query: Query<&mut Style, With<ReloadBar>>,
+) {
+    if player_query.is_empty() || text_query.is_empty() || bar_query.is_empty() {
+        return;
+    }
+
+    let mut text = text_query.single_mut();
+    let mut bar_style = bar_query.single_mut();
+    let Some(Ok((weapon, ammo, reloading))) = player_query
+        .single()
+        .active_gun()
+        .map(|gun| gun_query.get(gun))
+    else {
+        text.sections[0].value.clear();
+        bar_style.width = Val::Percent(0.0);
+        return;
+    };
+
+    let name = weapon_defs
+        .get(&weapon.0)
+        .map_or("", |weapon| weapon.name.as_str());
+    text.sections[0].value = format!("{name}\n{} / {}", ammo.magazine, ammo.reserve);
+    bar_style.width = Val::Percent(reloading.map_or(0.0, |reloading| reloading.progress() * 100.0));
+}
+
+fn spawn_dash_hud(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(20.0),
+                    bottom: Val::Px(20.0),
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(8.0),
+                    ..default()
+                },
+                ..default()
+            },
+            GameEntity,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Dash",
+                TextStyle {
+                    font_size: 30.0,
+                    color: Color::BLACK,
+                    ..Default::default()
+                },
+            ));
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Px(120.0),
+                        height: Val::Px(12.0),
+                        border: UiRect::all(Val::Px(2.0)),
+                        ..default()
+                    },
+                    border_color: BorderColor(Color::BLACK),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn((
+                        NodeBundle {
+                            style: Style {
+                                width: Val::Percent(100.0),
+                                height: Val::Percent(100.0),
+                                ..default()
+                            },
+                            background_color: BackgroundColor(Color::BLACK),
+                            ..default()
+                        },
+                        DashBar,
+                    ));
+                });
+        });
+}
+
+fn update_dash_hud(
+    player_query: Query<&Dash, With<Player>>,
+    mut bar_query: Query<&mut Style, With<DashBar>>,
+) {
+    if player_query.is_empty() || bar_query.is_empty() {
+        return;
+    }
+
+    bar_query.single_mut().width = Val::Percent(player_query.single().progress() * 100.0);
+}
+
+fn spawn_wave_text(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    top: Val::Px(20.0),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            GameEntity,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 40.0,
+                        color: Color::BLACK,
+                        ..Default::default()
+                    },
+                )
+                .with_text_justify(JustifyText::Center),
+                WaveText,
+            ));
+        });
+}
+
+fn update_wave_text(director: Res<WaveDirector>, mut text_query: Query<&mut Text, With<WaveText>>) {
+    if text_query.is_empty() {
+        return;
+    }
+
+    let mut text = text_query.single_mut();
+    text.sections[0].value = match director.phase {
+        WavePhase::Active => format!("Wave {}", director.wave),
+        WavePhase::Break => format!(
+            "Wave {} cleared\nNext wave in {:.0}",
+            director.wave,
+            director.timer.remaining_secs().ceil()
+        ),
+    };
+}
+
+fn spawn_boss_hud(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    display: Display::None,
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    top: Val::Px(120.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(8.0),
+                    ..default()
+                },
+                ..default()
+            },
+            BossHud,
+            GameEntity,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 40.0,
+                        color: Color::BLACK,
+                        ..Default::default()
+                    },
+                ),
+                BossNameText,
+            ));
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Percent(50.0),
+                        height: Val::Px(20.0),
+                        border: UiRect::all(Val::Px(2.0)),
+                        ..default()
+                    },
+                    border_color: BorderColor(Color::BLACK),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn((
+                        NodeBundle {
+                            style: Style {
+                                width: Val::Percent(100.0),
+                                height: Val::Percent(100.0),
+                                ..default()
+                            },
+                            background_color: BackgroundColor(BOSS_HEALTH_BAR_COLOR),
+                            ..default()
+                        },
+                        BossHealthBar,
+                    ));
+                });
+        });
+}
+
+fn update_boss_hud(
+    boss_query: Query<(&Enemy, &Boss)>,
+    mut hud_query: Query<&mut Style, (With<BossHud>, Without<BossHealthBar>)>,
+    mut text_query: Query<&mut Text, With<BossNameText>>,
+    mut bar_query: Query<&mut Style, (With<BossHealthBar>, Without<BossHud>)>,
+) {
+    if hud_query.is_empty() || text_query.is_empty() || bar_query.is_empty() {
+        return;
+    }
+
+    let mut hud_style = hud_query.single_mut();
+    let Some((enemy, boss)) = boss_query.iter().next() else {
+        hud_style.display = Display::None;
+        return;
+    };
+
+    hud_style.display = Display::Flex;
+    text_query.single_mut().sections[0]
+        .value
+        .clone_from(&boss.name);
+    bar_query.single_mut().width =
+        Val::Percent((enemy.health / boss.max_health).clamp(0.0, 1.0) * 100.0);
+}
+
+fn spawn_experience_hud(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Px(12.0),
+                    top: Val::Px(0.0),
+                    ..default()
+                },
+                ..default()
+            },
+            GameEntity,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                NodeBundle {
+                    style: Style {
+                        width: Val::Percent(0.0),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    background_color: BackgroundColor(XP_GEM_COLOR),
+                    ..default()
+                },
+                ExperienceBar,
+            ));
+        });
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 40.0,
+                color: Color::BLACK,
+                ..Default::default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(20.0),
+            right: Val::Px(20.0),
+            ..default()
+        }),
+        LevelText,
+        GameEntity,
+    ));
+}
+
+fn update_experience_hud(
+    player_query: Query<&Experience, With<Player>>,
+    mut text_query: Query<&mut Text, With<LevelText>>,
+    mut bar_query: Query<&mut Style, With<ExperienceBar>>,
+) {
+    if player_query.is_empty() || text_query.is_empty() || bar_query.is_empty() {
+        return;
+    }
+
+    let experience = player_query.single();
+    text_query.single_mut().sections[0].value = format!("Level {}", experience.level);
+    bar_query.single_mut().width = Val::Percent(experience.progress().min(1.0) * 100.0);
+}
+
+fn spawn_buff_text(mut commands: Commands) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 30.0,
+                color: Color::BLACK,
+                ..Default::default()
+            },
+        )
+        .with_text_justify(JustifyText::Right)
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(70.0),
+            right: Val::Px(20.0),
+            ..default()
+        }),
+        BuffText,
+        GameEntity,
+    ));
+}
+
+fn update_buff_text(
+    player_query: Query<&Buffs, With<Player>>,
+    mut text_query: Query<&mut Text, With<BuffText>>,
+) {
+    if player_query.is_empty() || text_query.is_empty() {
+        return;
+    }
+
+    let mut buffs: Vec<(&BuffKind, &Timer)> = player_query.single().0.iter().collect();
+    buffs.sort_by_key(|(_, timer)| timer.remaining());
+    text_query.single_mut().sections[0].value = buffs
+        .into_iter()
+        .map(|(kind, timer)| {
+            let name = match kind {
+                BuffKind::FireRate => "Fire rate",
+                BuffKind::Speed => "Speed",
+                BuffKind::Invincibility => "Invincible",
+            };
+            format!("{name} {:.0}s", timer.remaining_secs().ceil())
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+}
+
+fn despawn_level_up_menu(
+    mut commands: Commands,
+    menu_item_query: Query<Entity, With<LevelUpMenuItem>>,
+) {
+    for e in menu_item_query.iter() {
+        commands.entity(e).despawn_recursive();
+    }
+}
+
+fn setup_level_up_menu(
+    mut commands: Commands,
+    choices: Res<UpgradeChoices>,
+    player_query: Query<&Experience, With<Player>>,
+) {
+    let level = player_query
+        .get_single()
+        .map_or(0, |experience| experience.level);
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    row_gap: Val::Px(20.0),
+                    ..default()
+                },
+                background_color: BackgroundColor(OVERLAY_COLOR),
+                ..default()
+            },
+            LevelUpMenuItem,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                format!("Level {level}!"),
+                TextStyle {
+                    font_size: 60.0,
+                    color: Color::BLACK,
+                    ..Default::default()
+                },
+            ));
+
+            for (index, upgrade) in choices.0.iter().enumerate() {
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(500.0),
+                                padding: UiRect::all(Val::Px(10.0)),
+                                border: UiRect::all(Val::Px(5.0)),
+                                flex_direction: FlexDirection::Column,
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+                            border_color: BorderColor(Color::BLACK),
+                            image: UiImage::default().with_color(BUTTON_COLOR),
+                            ..default()
+                        },
+                        LevelUpButton(index),
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle::from_section(
+                            upgrade.title(),
+                            TextStyle {
+                                font_size: 40.0,
+                                color: Color::BLACK,
+                                ..Default::default()
+                            },
+                        ));
+                        parent.spawn(TextBundle::from_section(
+                            upgrade.description(),
+                            TextStyle {
+                                font_size: 25.0,
+                                color: Color::BLACK,
+                                ..Default::default()
+                            },
+                        ));
+                    });
+            }
+        });
+}
+
+fn handle_level_up_buttons(
+    mut pressed_events: EventReader<MenuButtonPressed>,
+    button_query: Query<&LevelUpButton>,
+    mut chosen_events: EventWriter<UpgradeChosenEvent>,
+) {
+    for event in pressed_events.read() {
+        if let Ok(button) = button_query.get(event.0) {
+            chosen_events.send(UpgradeChosenEvent(button.0));
+        }
+    }
+}
+
+fn despawn_pause_menu(mut commands: Commands, menu_item_query: Query<Entity, With<PauseMenuItem>>) {
+    for e in menu_item_query.iter() {
+        commands.entity(e).despawn_recursive();
+    }
+}
+
+fn setup_pause_menu(mut commands: Commands) {
+    let menu = spawn_menu_screen(&mut commands, "Paused");
+    commands
+        .entity(menu)
+        .insert(PauseMenuItem)
+        .with_children(|parent| {
+            spawn_menu_button(parent, "Resume", MenuAction::Resume);
+            spawn_menu_button(parent, "Settings", MenuAction::Settings);
+            spawn_menu_button(parent, "Quit to Main Menu", MenuAction::MainMenu);
+            spawn_menu_button(parent, "Quit", MenuAction::Quit);
+        });
+}
+
+fn despawn_game_over_menu(
+    mut commands: Commands,
+    menu_item_query: Query<Entity, With<GameOverMenuItem>>,
+) {
+    for e in menu_item_query.iter() {
+        commands.entity(e).despawn_recursive();
+    }
+}
+
+fn setup_game_over_menu(
+    mut commands: Commands,
+    summary: Res<RunSummary>,
+    director: Res<WaveDirector>,
+    player_query: Query<&Experience, With<Player>>,
+) {
+    let level = player_query
+        .get_single()
+        .map_or(0, |experience| experience.level);
+    let lines = [
+        format!("Survived {}", summary.formatted_time()),
+        format!("Kills {}", summary.kills),
+        format!("Damage dealt {:.0}", summary.damage_dealt),
+        format!("Wave {}", director.wave),
+        format!("Level {level}"),
+    ];
+
+    let menu = spawn_menu_screen(&mut commands, "Game Over");
+    commands
+        .entity(menu)
+        .insert(GameOverMenuItem)
+        .with_children(|parent| {
+            parent.spawn(
+                TextBundle::from_section(
+                    lines.join("\n"),
+                    TextStyle {
+                        font_size: 40.0,
+                        color: Color::BLACK,
+                        ..Default::default()
+                    },
+                )
+                .with_text_justify(JustifyText::Center),
+            );
+            spawn_menu_button(parent, "Retry", MenuAction::Retry);
+            spawn_menu_button(parent, "Main Menu", MenuAction::MainMenu);
+        });
+}
+
+fn settings_label(action: MenuAction, settings: &Settings, pending: &PendingRebind) -> String {
+    let on_off = |value: bool| if value { "On" } else { "Off" };
+    match action {
+        MenuAction::CycleWindowMode => {
+            let mode = match settings.video.window_mode {
+                WindowMode::Windowed => "Windowed",
+                WindowMode::BorderlessFullscreen => "Borderless",
+                _ => "Fullscreen",
+            };
+            format!("Window: {mode}")
+        }
+        MenuAction::CycleResolution => {
+            let (width, height) = settings.video.resolution;
+            format!("Resolution: {width}x{height}")
+        }
+        MenuAction::ToggleVsync => format!("VSync: {}", on_off(settings.video.vsync)),
+        MenuAction::StepVolume(channel) => {
+            let name = match channel {
+                VolumeChannel::Master => "Master",
+                VolumeChannel::Music => "Music",
+            };
+            format!("{name}: {:.0}%", settings.audio.get(channel) * 100.0)
+        }
+        MenuAction::Rebind(action) if pending.0 == Some(action) => {
+            format!("{}: press a button", action.name())
+        }
+        MenuAction::Rebind(action) => {
+            let bindings: Vec<String> = settings
+                .controls
+                .bindings(action)
+                .iter()
+                .map(InputBinding::name)
+                .collect();
+            format!("{}: {}", action.name(), bindings.join(" / "))
+        }
+        _ => String::new(),
+    }
+}
+
+fn despawn_settings_menu(
+    mut commands: Commands,
+    menu_item_query: Query<Entity, With<SettingsMenuItem>>,
+) {
+    for e in menu_item_query.iter() {
+        commands.entity(e).despawn_recursive();
+    }
+}
+
+fn menu_column() -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(10.0),
+            ..default()
+        },
+        ..default()
+    }
+}
+
+fn setup_settings_menu(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    pending: Res<PendingRebind>,
+) {
+    let actions = [
+        MenuAction::CycleWindowMode,
+        MenuAction::CycleResolution,
+        MenuAction::ToggleVsync,
+        MenuAction::StepVolume(VolumeChannel::Master),
+        MenuAction::StepVolume(VolumeChannel::Music),
+    ];
+
+    let menu = spawn_menu_screen(&mut commands, "Settings");
+    commands
+        .entity(menu)
+        .insert(SettingsMenuItem)
+        .with_children(|parent| {
+            parent.spawn(menu_column()).with_children(|parent| {
+                for action in actions {
+                    spawn_menu_button(parent, &settings_label(action, &settings, &pending), action);
+                }
+                spawn_menu_button(parent, "Controls", MenuAction::Controls);
+                spawn_menu_button(parent, "Back", (MenuAction::Back, BackButton));
+            });
+        });
+}
+
+fn despawn_controls_menu(
+    mut commands: Commands,
+    menu_item_query: Query<Entity, With<ControlsMenuItem>>,
+) {
+    for e in menu_item_query.iter() {
+        commands.entity(e).despawn_recursive();
+    }
+}
+
+fn setup_controls_menu(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    pending: Res<PendingRebind>,
+) {
+    let actions = Action::ALL.map(MenuAction::Rebind);
+    let (left, right) = actions.split_at(actions.len().div_ceil(2));
+
+    let menu = spawn_menu_screen(&mut commands, "Controls");
+    commands
+        .entity(menu)
+        .insert(ControlsMenuItem)
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        column_gap: Val::Px(20.0),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for column in [left, right] {
+                        parent.spawn(menu_column()).with_children(|parent| {
+                            for &action in column {
+                                spawn_menu_button(
+                                    parent,
+                                    &settings_label(action, &settings, &pending),
+                                    action,
+                                );
+                            }
+                        });
+                    }
+                });
+            spawn_menu_button(parent, "Back", (MenuAction::Back, BackButton));
+        });
+}
+
+fn handle_settings_actions(
+    mut pressed_events: EventReader<MenuButtonPressed>,
+    action_query: Query<&MenuAction>,
+    mut settings: ResMut<Settings>,
+    mut pending: ResMut<PendingRebind>,
+) {
+    for event in pressed_events.read() {
+        let Ok(action) = action_query.get(event.0) else {
+            continue;
+        };
+
+        match *action {
+            MenuAction::CycleWindowMode => settings.video.next_window_mode(),
+            MenuAction::CycleResolution => settings.video.next_resolution(),
+            MenuAction::ToggleVsync => settings.video.vsync = !settings.video.vsync,
+            MenuAction::StepVolume(channel) => settings.audio.step(channel),
+            MenuAction::Rebind(action) => pending.0 = Some(action),
+            _ => (),
+        }
+    }
+}
+
+fn update_settings_labels(
+    settings: Res<Settings>,
+    pending: Res<PendingRebind>,
+    action_query: Query<(&MenuAction, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    for (&action, children) in action_query.iter() {
+        let label = settings_label(action, &settings, &pending);
+        if label.is_empty() {
+            continue;
+        }
+
+        for &child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(child) {
+                text.sections[0].value.clone_from(&label);
+            }
+        }
+    }
+}
@@ -1,53 +1,231 @@
 use std::f32::consts::PI;
-use bevy::utils::Instant;
 
+use bevy::input::mouse::MouseWheel;
 use bevy::math::vec3;
 use bevy::prelude::*;
 use bevy::time::Stopwatch;
-use rand::Rng;
 
+use crate::collision::EnemyKdTree;
+use crate::enemy::Enemy;
+use crate::input::{Action, ActionState};
 use crate::player::Player;
-use crate::state::GameState;
+use crate::state::PlayState;
+use crate::stats::{Stat, Stats};
+use crate::weapon::{Weapon, WeaponDef};
+use crate::world::GameEntity;
 use crate::*;
 
 pub struct GunPlugin;
 
+const WEAPON_SLOT_KEYS: [KeyCode; 9] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+
 #[derive(Component)]
 pub struct Gun;
 #[derive(Component)]
 pub struct GunTimer(pub Stopwatch);
 #[derive(Component)]
-pub struct Bullet;
+pub struct Ammo {
+    pub magazine: u32,
+    pub reserve: u32,
+}
+#[derive(Component)]
+pub struct Reloading(pub Timer);
+#[derive(Component, Default)]
+pub struct WeaponInventory {
+    pub slots: Vec<Entity>,
+    pub active: usize,
+}
+#[derive(Component)]
+pub struct Bullet {
+    pub damage: f32,
+    pub gun: Entity,
+}
 #[derive(Component)]
-struct BulletDirection(Vec3);
+pub struct BulletDirection(pub Vec3);
 #[derive(Component)]
-struct SpawnInstant(Instant);
+pub struct Pierce {
+    pub remaining: u32,
+    pub hit_enemies: Vec<Entity>,
+}
+#[derive(Component)]
+pub struct Explosive {
+    pub radius: f32,
+    pub damage: f32,
+}
+#[derive(Component)]
+pub struct Homing {
+    pub turn_rate: f32,
+    pub acquisition_radius: f32,
+}
+#[derive(Component)]
+pub struct Bounce(pub u32);
+#[derive(Component)]
+struct BulletSpeed(f32);
+#[derive(Component)]
+struct BulletLifetime(Timer);
+#[derive(Component)]
+struct BulletRange(f32);
+
+type ReadyGunFilter = (With<Gun>, Without<Reloading>);
+type ReloadGunQuery<'w, 's> = Query<'w, 's, (&'static Ammo, &'static Weapon), ReadyGunFilter>;
+type FireGunQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Transform,
+        &'static mut GunTimer,
+        &'static mut Ammo,
+        &'static Weapon,
+    ),
+    ReadyGunFilter,
+>;
 
 impl Plugin for GunPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
             Update,
             (
+                handle_weapon_switch_input,
+                update_gun_visibility,
                 update_gun_transform,
+                update_gun_sprite,
+                tick_gun_timers,
                 despawn_old_bullets,
+                steer_homing_bullets,
                 update_bullets,
-                handle_gun_input,
+                update_reload,
+                // Chained so a reload started this frame already blocks firing.
+                (handle_reload_input, handle_gun_input).chain(),
             )
-                .run_if(in_state(GameState::InGame)),
+                .run_if(in_state(PlayState::Running)),
         );
     }
 }
 
+impl WeaponInventory {
+    pub fn active_gun(&self) -> Option<Entity> {
+        self.slots.get(self.active).copied()
+    }
+}
+
+impl Reloading {
+    pub fn progress(&self) -> f32 {
+        self.0.fraction()
+    }
+}
+
+pub fn spawn_gun(
+    commands: &mut Commands,
+    handle: &GlobalTextureAtlas,
+    weapon: Handle<WeaponDef>,
+    weapon_def: &WeaponDef,
+) -> Entity {
+    commands
+        .spawn((
+            SpriteBundle {
+                transform: Transform::from_translation(vec3(0.0, 0.0, 12.0))
+                    .with_scale(Vec3::splat(SPRITE_SCALE_FACTOR)),
+                texture: handle.image.clone().unwrap(),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            TextureAtlas {
+                layout: handle.layout.clone().unwrap(),
+                index: weapon_def.sprite_index,
+            },
+            Gun,
+            GunTimer(Stopwatch::new()),
+            Ammo {
+                magazine: weapon_def.magazine_size,
+                reserve: weapon_def.max_reserve_ammo,
+            },
+            Weapon(weapon),
+            GameEntity,
+        ))
+        .id()
+}
+
+fn handle_weapon_switch_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    actions: Res<ActionState>,
+    mut mouse_wheel_events: EventReader<MouseWheel>,
+    mut player_query: Query<&mut WeaponInventory, With<Player>>,
+) {
+    let scroll: f32 = mouse_wheel_events.read().map(|event| event.y).sum();
+
+    if player_query.is_empty() {
+        return;
+    }
+
+    let mut inventory = player_query.single_mut();
+    let num_slots = inventory.slots.len();
+    if num_slots == 0 {
+        return;
+    }
+
+    let mut active = inventory.active;
+    for (slot, key) in WEAPON_SLOT_KEYS.iter().enumerate().take(num_slots) {
+        if keyboard_input.just_pressed(*key) {
+            active = slot;
+        }
+    }
+
+    if scroll > 0.0 {
+        active = (active + num_slots - 1) % num_slots;
+    } else if scroll < 0.0 || actions.just_pressed(Action::SwitchWeapon) {
+        active = (active + 1) % num_slots;
+    }
+
+    if active != inventory.active {
+        inventory.active = active;
+    }
+}
+
+fn update_gun_visibility(
+    player_query: Query<&WeaponInventory, (With<Player>, Changed<WeaponInventory>)>,
+    mut gun_query: Query<(Entity, &mut Visibility), With<Gun>>,
+) {
+    let Ok(inventory) = player_query.get_single() else {
+        return;
+    };
+
+    let active_gun = inventory.active_gun();
+    for (entity, mut visibility) in gun_query.iter_mut() {
+        *visibility = if Some(entity) == active_gun {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+fn tick_gun_timers(time: Res<Time>, mut gun_query: Query<&mut GunTimer, With<Gun>>) {
+    for mut gun_timer in gun_query.iter_mut() {
+        gun_timer.0.tick(time.delta());
+    }
+}
+
 fn despawn_old_bullets(
     mut commands: Commands,
-    mut bullet_query: Query<(&SpawnInstant, Entity), With<Bullet>>,
+    time: Res<Time>,
+    mut bullet_query: Query<(&mut BulletLifetime, &BulletRange, Entity), With<Bullet>>,
 ) {
     if bullet_query.is_empty() {
         return;
     }
 
-    for (instant, entity) in bullet_query.iter_mut() {
-        if instant.0.elapsed().as_secs_f32() > BULLET_TIME_SECS {
+    for (mut lifetime, range, entity) in bullet_query.iter_mut() {
+        if lifetime.0.tick(time.delta()).finished() || range.0 <= 0.0 {
             commands.entity(entity).despawn();
         }
     }
@@ -55,19 +233,25 @@ fn despawn_old_bullets(
 
 fn update_gun_transform(
     cursor_pos: Res<CursorPosition>,
-    player_query: Query<&Transform, With<Player>>,
+    player_query: Query<(&Transform, &WeaponInventory), With<Player>>,
     mut gun_query: Query<(&mut Sprite, &mut Transform), (With<Gun>, Without<Player>)>,
 ) {
     if player_query.is_empty() || gun_query.is_empty() {
         return;
     }
 
-    let player_pos: Vec2 = player_query.single().translation.truncate();
+    let (player_transform, inventory) = player_query.single();
+    let player_pos: Vec2 = player_transform.translation.truncate();
     let cursor_pos: Vec2 = match cursor_pos.0 {
         Some(pos) => pos,
         None => player_pos,
     };
-    let (mut gun_sprite, mut gun_transform) = gun_query.single_mut();
+    let Some(active_gun) = inventory.active_gun() else {
+        return;
+    };
+    let Ok((mut gun_sprite, mut gun_transform)) = gun_query.get_mut(active_gun) else {
+        return;
+    };
 
     let mut gun_offset = Vec3::new(4.0, -8.0, 0.0);
     if cursor_pos.x < player_pos.x {
@@ -90,43 +274,147 @@ fn update_gun_transform(
     gun_transform.translation = new_gun_pos;
 }
 
-fn handle_gun_input(
+fn update_gun_sprite(
+    weapon_defs: Res<Assets<WeaponDef>>,
+    mut gun_query: Query<(&mut TextureAtlas, &Weapon), With<Gun>>,
+) {
+    for (mut texture_atlas, weapon) in gun_query.iter_mut() {
+        if let Some(weapon) = weapon_defs.get(&weapon.0) {
+            if texture_atlas.index != weapon.sprite_index {
+                texture_atlas.index = weapon.sprite_index;
+            }
+        }
+    }
+}
+
+fn start_reload(
+    commands: &mut Commands,
+    gun: Entity,
+    ammo: &Ammo,
+    weapon: &WeaponDef,
+    stats: &Stats,
+) {
+    if ammo.reserve == 0 || ammo.magazine >= weapon.magazine_size {
+        return;
+    }
+
+    commands.entity(gun).insert(Reloading(Timer::from_seconds(
+        weapon.reload_time / stats.get(Stat::ReloadSpeed),
+        TimerMode::Once,
+    )));
+}
+
+fn handle_reload_input(
+    mut commands: Commands,
+    actions: Res<ActionState>,
+    player_query: Query<(&WeaponInventory, &Stats), With<Player>>,
+    gun_query: ReloadGunQuery,
+    weapon_defs: Res<Assets<WeaponDef>>,
+) {
+    if player_query.is_empty() || !actions.just_pressed(Action::Reload) {
+        return;
+    }
+
+    let (inventory, stats) = player_query.single();
+    let Some(active_gun) = inventory.active_gun() else {
+        return;
+    };
+    let Ok((ammo, weapon)) = gun_query.get(active_gun) else {
+        return;
+    };
+
+    if let Some(weapon) = weapon_defs.get(&weapon.0) {
+        start_reload(&mut commands, active_gun, ammo, weapon, stats);
+    }
+}
+
+fn update_reload(
     mut commands: Commands,
     time: Res<Time>,
-    mut gun_query: Query<(&Transform, &mut GunTimer), With<Gun>>,
-    mouse_button_input: Res<ButtonInput<MouseButton>>,
+    player_query: Query<&WeaponInventory, With<Player>>,
+    mut gun_query: Query<(&mut Reloading, &mut Ammo, &Weapon), With<Gun>>,
+    weapon_defs: Res<Assets<WeaponDef>>,
+) {
+    if player_query.is_empty() {
+        return;
+    }
+
+    // Holstered guns keep their reload progress until they are drawn again.
+    let Some(active_gun) = player_query.single().active_gun() else {
+        return;
+    };
+    let Ok((mut reloading, mut ammo, weapon)) = gun_query.get_mut(active_gun) else {
+        return;
+    };
+    let Some(weapon) = weapon_defs.get(&weapon.0) else {
+        return;
+    };
+
+    if !reloading.0.tick(time.delta()).finished() {
+        return;
+    }
+
+    let refill = weapon
+        .magazine_size
+        .saturating_sub(ammo.magazine)
+        .min(ammo.reserve);
+    ammo.magazine += refill;
+    ammo.reserve -= refill;
+    commands.entity(active_gun).remove::<Reloading>();
+}
+
+fn handle_gun_input(
+    mut commands: Commands,
+    player_query: Query<(&WeaponInventory, &Stats), With<Player>>,
+    mut gun_query: FireGunQuery,
+    actions: Res<ActionState>,
     handle: Res<GlobalTextureAtlas>,
+    weapon_defs: Res<Assets<WeaponDef>>,
 ) {
-    if gun_query.is_empty() {
+    if player_query.is_empty() || gun_query.is_empty() {
         return;
     }
 
-    let (gun_transform, mut gun_timer) = gun_query.single_mut();
+    let (inventory, stats) = player_query.single();
+    let Some(active_gun) = inventory.active_gun() else {
+        return;
+    };
+    let Ok((gun_transform, mut gun_timer, mut ammo, weapon)) = gun_query.get_mut(active_gun) else {
+        return;
+    };
     let gun_pos: Vec2 = gun_transform.translation.truncate();
-    gun_timer.0.tick(time.delta());
 
-    if !mouse_button_input.pressed(MouseButton::Left) {
+    let Some(weapon) = weapon_defs.get(&weapon.0) else {
+        return;
+    };
+
+    if ammo.magazine == 0 {
+        start_reload(&mut commands, active_gun, &ammo, weapon, stats);
         return;
     }
 
-    if gun_timer.0.elapsed_secs() < BULLET_SPAWN_INTERVAL {
+    if !actions.pressed(Action::Fire) {
+        return;
+    }
+
+    if gun_timer.0.elapsed_secs() < weapon.fire_interval / stats.get(Stat::FireRate) {
         return;
     }
 
     gun_timer.0.reset();
+    ammo.magazine -= 1;
+    if ammo.magazine == 0 {
+        start_reload(&mut commands, active_gun, &ammo, weapon, stats);
+    }
 
     let rotation_90 = Quat::from_rotation_z(PI / 2.0);
     let bullet_direction: Vec3 = rotation_90.mul_vec3(gun_transform.local_x().into());
 
     let mut rng = rand::thread_rng();
-    for _ in 0..NUM_BULLETS_PER_SHOT {
-        let random_dir = Vec3 {
-            x: bullet_direction.x + rng.gen_range(-1.0..1.0),
-            y: bullet_direction.y + rng.gen_range(-1.0..1.0),
-            z: bullet_direction.z,
-        };
+    for angle in weapon.spread.angles(weapon.pellets, &mut rng) {
+        let spread_rotation = Quat::from_rotation_z(angle);
 
-        commands.spawn((
+        let mut bullet = commands.spawn((
             SpriteBundle {
                 transform: Transform::from_translation(vec3(gun_pos.x, gun_pos.y, 11.0))
                     .with_scale(Vec3::splat(SPRITE_SCALE_FACTOR)),
@@ -135,24 +423,209 @@ fn handle_gun_input(
             },
             TextureAtlas {
                 layout: handle.layout.clone().unwrap(),
-                index: 16,
+                index: weapon.projectile_sprite_index,
+            },
+            Bullet {
+                damage: weapon.damage * stats.get(Stat::Damage),
+                gun: active_gun,
+            },
+            BulletDirection(spread_rotation * bullet_direction),
+            BulletSpeed(weapon.projectile_speed * stats.get(Stat::ProjectileSpeed)),
+            BulletLifetime(Timer::from_seconds(weapon.lifetime, TimerMode::Once)),
+            BulletRange(weapon.max_range),
+            Pierce {
+                remaining: weapon.pierce,
+                hit_enemies: Vec::new(),
             },
-            Bullet,
-            BulletDirection(bullet_direction + random_dir),
-            SpawnInstant(Instant::now()),
+            Bounce(weapon.bounces),
+            GameEntity,
         ));
+        if let Some(homing) = weapon.homing {
+            bullet.insert(Homing {
+                turn_rate: homing.turn_rate,
+                acquisition_radius: homing.acquisition_radius,
+            });
+        }
+        if let Some(explosion) = weapon.explosion {
+            bullet.insert(Explosive {
+                radius: explosion.radius,
+                damage: explosion.damage,
+            });
+        }
+    }
+}
+
+fn steer_homing_bullets(
+    time: Res<Time>,
+    tree: Res<EnemyKdTree>,
+    enemy_query: Query<&Transform, (With<Enemy>, Without<Bullet>)>,
+    mut bullet_query: Query<(&Transform, &mut BulletDirection, &Homing), With<Bullet>>,
+) {
+    if bullet_query.is_empty() || tree.is_empty() {
+        return;
+    }
+
+    for (t, mut dir, homing) in bullet_query.iter_mut() {
+        let pos = t.translation.truncate();
+        let Some((target, indexed_pos)) = tree.nearest(pos, homing.acquisition_radius) else {
+            continue;
+        };
+        // The tree is only rebuilt periodically, prefer the target's current position.
+        let target_pos = enemy_query
+            .get(target)
+            .map_or(indexed_pos, |target| target.translation.truncate());
+
+        let current = dir.0.truncate();
+        let desired = target_pos - pos;
+        if desired == Vec2::ZERO || current == Vec2::ZERO {
+            continue;
+        }
+
+        let max_turn = homing.turn_rate.to_radians() * time.delta_seconds();
+        let turn = current.angle_between(desired).clamp(-max_turn, max_turn);
+        dir.0 = (Vec2::from_angle(turn).rotate(current)).extend(dir.0.z);
     }
 }
 
 fn update_bullets(
-    mut bullet_query: Query<(&mut Transform, &BulletDirection), With<Bullet>>,
+    mut bullet_query: Query<
+        (
+            &mut Transform,
+            &BulletDirection,
+            &BulletSpeed,
+            &mut BulletRange,
+        ),
+        With<Bullet>,
+    >,
     time: Res<Time>,
 ) {
     if bullet_query.is_empty() {
         return;
     }
 
-    for (mut t, dir) in bullet_query.iter_mut() {
-        t.translation += dir.0.normalize() * Vec3::splat(BULLET_SPEED * time.delta_seconds());
+    for (mut t, dir, speed, mut range) in bullet_query.iter_mut() {
+        let distance = speed.0 * time.delta_seconds();
+        t.translation += dir.0.normalize() * Vec3::splat(distance);
+        range.0 -= distance;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy::time::update_virtual_time;
+
+    use super::*;
+    use crate::test_utils::world_with_time;
+
+    fn spawn_reloading_gun(world: &mut World, weapon: &Handle<WeaponDef>) -> Entity {
+        world
+            .spawn((
+                Gun,
+                Ammo {
+                    magazine: 0,
+                    reserve: 100,
+                },
+                Weapon(weapon.clone()),
+                Reloading(Timer::from_seconds(1.0, TimerMode::Once)),
+            ))
+            .id()
+    }
+
+    fn spawn_bullet(world: &mut World, lifetime: f32, range: f32) -> Entity {
+        world
+            .spawn((
+                Bullet {
+                    damage: 1.0,
+                    gun: Entity::PLACEHOLDER,
+                },
+                Transform::default(),
+                BulletDirection(Vec3::X),
+                BulletSpeed(100.0),
+                BulletLifetime(Timer::from_seconds(lifetime, TimerMode::Once)),
+                BulletRange(range),
+            ))
+            .id()
+    }
+
+    // Advances the clocks the way `TimePlugin` does for a frame that took `delta`.
+    fn step(world: &mut World, delta: Duration) {
+        let mut real = Time::<Real>::default();
+        real.advance_by(delta);
+        world.resource_scope(|world, mut virtual_time: Mut<Time<Virtual>>| {
+            update_virtual_time(&mut world.resource_mut::<Time>(), &mut virtual_time, &real);
+        });
+        world.run_system_once(update_bullets);
+        world.run_system_once(despawn_old_bullets);
+    }
+
+    fn bullet_world() -> World {
+        let mut world = world_with_time(Duration::ZERO);
+        world.init_resource::<Time<Virtual>>();
+        world
+    }
+
+    #[test]
+    fn bullets_stop_aging_while_paused() {
+        let mut world = bullet_world();
+        let bullet = spawn_bullet(&mut world, 0.1, 1000.0);
+
+        world.resource_mut::<Time<Virtual>>().pause();
+        step(&mut world, Duration::from_secs(1));
+        let lifetime = world.get::<BulletLifetime>(bullet).unwrap();
+        assert_eq!(lifetime.0.elapsed(), Duration::ZERO);
+        assert_eq!(world.get::<BulletRange>(bullet).unwrap().0, 1000.0);
+
+        world.resource_mut::<Time<Virtual>>().unpause();
+        step(&mut world, Duration::from_millis(200));
+        assert!(world.get_entity(bullet).is_none());
+    }
+
+    #[test]
+    fn bullets_despawn_at_max_range() {
+        let mut world = bullet_world();
+        let bullet = spawn_bullet(&mut world, 10.0, 30.0);
+
+        step(&mut world, Duration::from_millis(100));
+        assert!(world.get_entity(bullet).is_some());
+
+        step(&mut world, Duration::from_millis(250));
+        assert!(world.get_entity(bullet).is_none());
+    }
+
+    #[test]
+    fn only_the_drawn_gun_reloads() {
+        let mut weapon_defs = Assets::<WeaponDef>::default();
+        let weapon = weapon_defs.add(WeaponDef {
+            magazine_size: 30,
+            ..default()
+        });
+
+        let mut world = world_with_time(Duration::from_secs(1));
+        world.insert_resource(weapon_defs);
+
+        let drawn = spawn_reloading_gun(&mut world, &weapon);
+        let holstered = spawn_reloading_gun(&mut world, &weapon);
+        world.spawn((
+            Player,
+            WeaponInventory {
+                slots: vec![drawn, holstered],
+                active: 0,
+            },
+        ));
+
+        world.run_system_once(update_reload);
+
+        let ammo = world.get::<Ammo>(drawn).unwrap();
+        assert_eq!(ammo.magazine, 30);
+        assert_eq!(ammo.reserve, 70);
+        assert!(world.get::<Reloading>(drawn).is_none());
+
+        let ammo = world.get::<Ammo>(holstered).unwrap();
+        assert_eq!(ammo.magazine, 0);
+        assert_eq!(ammo.reserve, 100);
+        assert_eq!(world.get::<Reloading>(holstered).unwrap().progress(), 0.0);
     }
 }
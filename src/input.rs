@@ -0,0 +1,321 @@
+use std::collections::{BTreeMap, HashSet};
+
+use bevy::{input::InputSystem, prelude::*, window::CursorMoved};
+use serde::{Deserialize, Serialize};
+
+use crate::settings::Settings;
+use crate::*;
+
+pub struct ActionPlugin;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Fire,
+    Reload,
+    Dash,
+    SwitchWeapon,
+    Pause,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputBinding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButtonType),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct InputMap(pub BTreeMap<Action, Vec<InputBinding>>);
+
+#[derive(Resource, Default)]
+pub struct ActionState {
+    pressed: HashSet<Action>,
+    just_pressed: HashSet<Action>,
+    pub movement: Vec2,
+    pub aim: Option<Vec2>,
+}
+
+impl Plugin for ActionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActionState>().add_systems(
+            PreUpdate,
+            (update_action_buttons, update_action_axes)
+                .chain()
+                .after(InputSystem),
+        );
+    }
+}
+
+impl Action {
+    pub const ALL: [Action; 9] = [
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Fire,
+        Action::Reload,
+        Action::Dash,
+        Action::SwitchWeapon,
+        Action::Pause,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::MoveUp => "Move up",
+            Action::MoveDown => "Move down",
+            Action::MoveLeft => "Move left",
+            Action::MoveRight => "Move right",
+            Action::Fire => "Fire",
+            Action::Reload => "Reload",
+            Action::Dash => "Dash",
+            Action::SwitchWeapon => "Switch weapon",
+            Action::Pause => "Pause",
+        }
+    }
+
+    fn default_bindings(&self) -> Vec<InputBinding> {
+        use InputBinding::*;
+        match self {
+            Action::MoveUp => vec![
+                Key(KeyCode::KeyW),
+                Key(KeyCode::ArrowUp),
+                Gamepad(GamepadButtonType::DPadUp),
+            ],
+            Action::MoveDown => vec![
+                Key(KeyCode::KeyS),
+                Key(KeyCode::ArrowDown),
+                Gamepad(GamepadButtonType::DPadDown),
+            ],
+            Action::MoveLeft => vec![
+                Key(KeyCode::KeyA),
+                Key(KeyCode::ArrowLeft),
+                Gamepad(GamepadButtonType::DPadLeft),
+            ],
+            Action::MoveRight => vec![
+                Key(KeyCode::KeyD),
+                Key(KeyCode::ArrowRight),
+                Gamepad(GamepadButtonType::DPadRight),
+            ],
+            Action::Fire => vec![
+                Mouse(MouseButton::Left),
+                Gamepad(GamepadButtonType::RightTrigger2),
+            ],
+            Action::Reload => vec![Key(KeyCode::KeyR), Gamepad(GamepadButtonType::West)],
+            Action::Dash => vec![
+                Key(KeyCode::Space),
+                Key(KeyCode::ShiftLeft),
+                Gamepad(GamepadButtonType::South),
+            ],
+            Action::SwitchWeapon => vec![Key(KeyCode::KeyQ), Gamepad(GamepadButtonType::North)],
+            Action::Pause => vec![Key(KeyCode::Escape), Gamepad(GamepadButtonType::Start)],
+        }
+    }
+}
+
+impl InputBinding {
+    pub fn is_gamepad(&self) -> bool {
+        matches!(self, InputBinding::Gamepad(_))
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            InputBinding::Key(key) => {
+                let name = format!("{key:?}");
+                name.trim_start_matches("Key")
+                    .trim_start_matches("Digit")
+                    .to_string()
+            }
+            InputBinding::Mouse(MouseButton::Left) => "Left Click".to_string(),
+            InputBinding::Mouse(MouseButton::Right) => "Right Click".to_string(),
+            InputBinding::Mouse(MouseButton::Middle) => "Middle Click".to_string(),
+            InputBinding::Mouse(button) => format!("Mouse {button:?}"),
+            InputBinding::Gamepad(button) => match button {
+                GamepadButtonType::South => "A".to_string(),
+                GamepadButtonType::East => "B".to_string(),
+                GamepadButtonType::West => "X".to_string(),
+                GamepadButtonType::North => "Y".to_string(),
+                GamepadButtonType::LeftTrigger => "LB".to_string(),
+                GamepadButtonType::LeftTrigger2 => "LT".to_string(),
+                GamepadButtonType::RightTrigger => "RB".to_string(),
+                GamepadButtonType::RightTrigger2 => "RT".to_string(),
+                GamepadButtonType::LeftThumb => "L3".to_string(),
+                GamepadButtonType::RightThumb => "R3".to_string(),
+                GamepadButtonType::DPadUp => "D-Pad Up".to_string(),
+                GamepadButtonType::DPadDown => "D-Pad Down".to_string(),
+                GamepadButtonType::DPadLeft => "D-Pad Left".to_string(),
+                GamepadButtonType::DPadRight => "D-Pad Right".to_string(),
+                button => format!("{button:?}"),
+            },
+        }
+    }
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        Self(
+            Action::ALL
+                .iter()
+                .map(|action| (*action, action.default_bindings()))
+                .collect(),
+        )
+    }
+}
+
+impl InputMap {
+    pub fn bindings(&self, action: Action) -> &[InputBinding] {
+        self.0.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn rebind(&mut self, action: Action, binding: InputBinding) {
+        let bindings = self.0.entry(action).or_default();
+        bindings.retain(|bound| bound.is_gamepad() != binding.is_gamepad());
+        bindings.push(binding);
+    }
+
+    pub fn add_missing_defaults(&mut self) {
+        for action in Action::ALL {
+            self.0
+                .entry(action)
+                .or_insert_with(|| action.default_bindings());
+        }
+    }
+}
+
+impl ActionState {
+    pub fn pressed(&self, action: Action) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.just_pressed.contains(&action)
+    }
+}
+
+fn update_action_buttons(
+    settings: Res<Settings>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mouse_button_input: Res<ButtonInput<MouseButton>>,
+    gamepad_input: Res<ButtonInput<GamepadButton>>,
+    mut actions: ResMut<ActionState>,
+) {
+    let pressed = |binding: &InputBinding| match binding {
+        InputBinding::Key(key) => keyboard_input.pressed(*key),
+        InputBinding::Mouse(button) => mouse_button_input.pressed(*button),
+        InputBinding::Gamepad(button_type) => gamepad_input
+            .get_pressed()
+            .any(|button| button.button_type == *button_type),
+    };
+    let just_pressed = |binding: &InputBinding| match binding {
+        InputBinding::Key(key) => keyboard_input.just_pressed(*key),
+        InputBinding::Mouse(button) => mouse_button_input.just_pressed(*button),
+        InputBinding::Gamepad(button_type) => gamepad_input
+            .get_just_pressed()
+            .any(|button| button.button_type == *button_type),
+    };
+
+    actions.pressed.clear();
+    actions.just_pressed.clear();
+    for action in Action::ALL {
+        let bindings = settings.controls.bindings(action);
+        if bindings.iter().any(pressed) {
+            actions.pressed.insert(action);
+        }
+        if bindings.iter().any(just_pressed) {
+            actions.just_pressed.insert(action);
+        }
+    }
+}
+
+fn update_action_axes(
+    gamepads: Res<Gamepads>,
+    axes: Res<Axis<GamepadAxis>>,
+    mut cursor_moved_events: EventReader<CursorMoved>,
+    mut actions: ResMut<ActionState>,
+) {
+    let stick = |gamepad: Gamepad, x: GamepadAxisType, y: GamepadAxisType| {
+        let value = Vec2::new(
+            axes.get(GamepadAxis::new(gamepad, x)).unwrap_or(0.0),
+            axes.get(GamepadAxis::new(gamepad, y)).unwrap_or(0.0),
+        );
+        (value.length() > GAMEPAD_DEADZONE).then_some(value.clamp_length_max(1.0))
+    };
+    let left_stick = gamepads.iter().find_map(|gamepad| {
+        stick(
+            gamepad,
+            GamepadAxisType::LeftStickX,
+            GamepadAxisType::LeftStickY,
+        )
+    });
+    let right_stick = gamepads.iter().find_map(|gamepad| {
+        stick(
+            gamepad,
+            GamepadAxisType::RightStickX,
+            GamepadAxisType::RightStickY,
+        )
+    });
+
+    let mut digital = Vec2::ZERO;
+    if actions.pressed(Action::MoveUp) {
+        digital.y += 1.0;
+    }
+    if actions.pressed(Action::MoveDown) {
+        digital.y -= 1.0;
+    }
+    if actions.pressed(Action::MoveLeft) {
+        digital.x -= 1.0;
+    }
+    if actions.pressed(Action::MoveRight) {
+        digital.x += 1.0;
+    }
+    actions.movement = left_stick.unwrap_or(digital.normalize_or_zero());
+
+    let cursor_moved = cursor_moved_events.read().count() > 0;
+    if let Some(aim) = right_stick {
+        actions.aim = Some(aim.normalize());
+    } else if cursor_moved {
+        actions.aim = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rebinding_keeps_the_other_device() {
+        let mut map = InputMap::default();
+
+        map.rebind(Action::Reload, InputBinding::Mouse(MouseButton::Right));
+
+        assert_eq!(
+            map.bindings(Action::Reload),
+            [
+                InputBinding::Gamepad(GamepadButtonType::West),
+                InputBinding::Mouse(MouseButton::Right),
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_actions_get_their_default_bindings() {
+        let mut map = InputMap(BTreeMap::from([(
+            Action::Fire,
+            vec![InputBinding::Key(KeyCode::KeyF)],
+        )]));
+
+        map.add_missing_defaults();
+
+        assert_eq!(
+            map.bindings(Action::Fire),
+            [InputBinding::Key(KeyCode::KeyF)]
+        );
+        assert_eq!(
+            map.bindings(Action::Pause),
+            Action::Pause.default_bindings()
+        );
+    }
+}
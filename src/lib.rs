@@ -1,16 +1,33 @@
 pub mod animation;
+pub mod boss;
 pub mod camera;
-pub mod close_on_esc;
 pub mod collision;
 pub mod constants;
+pub mod damage;
+pub mod dash;
 pub mod enemy;
+pub mod experience;
 pub mod gui;
 pub mod gun;
+pub mod input;
+pub mod menu;
+pub mod passive;
+pub mod pickup;
 pub mod player;
+pub mod projectile;
 pub mod resources;
+pub mod settings;
 pub mod sound;
 pub mod state;
+pub mod stats;
+pub mod summary;
+pub mod upgrade;
+pub mod wave;
+pub mod weapon;
 pub mod world;
 
 pub use constants::*;
 pub use resources::*;
+
+#[cfg(test)]
+mod test_utils;
@@ -4,22 +4,40 @@
 use bevy::prelude::*;
 use bevy::render::settings::{Backends, RenderCreation, WgpuSettings};
 use bevy::render::RenderPlugin;
-use bevy::window::WindowMode;
 
 use animation::AnimationPlugin;
 use bevy_top_down_shooter::*;
+use boss::BossPlugin;
 use camera::FollowCameraPlugin;
-use close_on_esc::CloseOnEscapePlugin;
 use collision::CollisionPlugin;
+use damage::DamagePlugin;
+use dash::DashPlugin;
 use enemy::EnemyPlugin;
+use experience::ExperiencePlugin;
 use gui::GuiPlugin;
 use gun::GunPlugin;
+use input::ActionPlugin;
+use menu::MenuPlugin;
+use passive::PassiveWeaponPlugin;
+use pickup::PickupPlugin;
 use player::PlayerPlugin;
+use projectile::EnemyProjectilePlugin;
+use settings::{Settings, SettingsLoadError, SettingsPlugin};
 use sound::SoundPlugin;
-use state::GameState;
+use state::{GameState, PlayStatePlugin};
+use summary::RunSummaryPlugin;
+use upgrade::UpgradePlugin;
+use wave::WavePlugin;
+use weapon::WeaponPlugin;
 use world::WorldPlugin;
 
 fn main() {
+    // Logging only starts with the app, so a load error is reported by `SettingsPlugin`.
+    let (settings, load_error) = match Settings::load() {
+        Ok(settings) => (settings, None),
+        Err(error) => (Settings::default(), Some(error)),
+    };
+
     App::new()
         .init_state::<GameState>()
         .add_plugins(
@@ -33,28 +51,39 @@ fn main() {
                 })
                 .set(ImagePlugin::default_nearest())
                 .set(WindowPlugin {
-                    primary_window: Some(Window {
-                        resizable: false,
-                        focused: true,
-                        mode: WindowMode::BorderlessFullscreen,
-                        ..default()
-                    }),
+                    primary_window: Some(settings.window()),
                     ..default()
                 }),
         )
         // External plugins
         // .add_plugins(LogDiagnosticsPlugin::default())
         // .add_plugins(FrameTimeDiagnosticsPlugin)
-        .add_plugins(CloseOnEscapePlugin)
+        .insert_resource(settings)
+        .insert_resource(SettingsLoadError(load_error))
+        .add_plugins(SettingsPlugin)
+        .add_plugins(ActionPlugin)
+        .add_plugins(PlayStatePlugin)
         .add_plugins(FollowCameraPlugin)
         .add_plugins(ResourcesPlugin)
         .add_plugins(SoundPlugin)
         .add_plugins(WorldPlugin)
         .add_plugins(PlayerPlugin)
+        .add_plugins(DashPlugin)
+        .add_plugins(WeaponPlugin)
         .add_plugins(GunPlugin)
+        .add_plugins(PassiveWeaponPlugin)
         .add_plugins(EnemyPlugin)
+        .add_plugins(WavePlugin)
+        .add_plugins(BossPlugin)
+        .add_plugins(EnemyProjectilePlugin)
         .add_plugins(AnimationPlugin)
         .add_plugins(CollisionPlugin)
+        .add_plugins(DamagePlugin)
+        .add_plugins(ExperiencePlugin)
+        .add_plugins(PickupPlugin)
+        .add_plugins(UpgradePlugin)
+        .add_plugins(RunSummaryPlugin)
+        .add_plugins(MenuPlugin)
         .add_plugins(GuiPlugin)
         .insert_resource(ClearColor(Color::srgb_u8(
             BG_COLOR.0, BG_COLOR.1, BG_COLOR.2,
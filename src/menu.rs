@@ -0,0 +1,219 @@
+use bevy::prelude::*;
+
+use crate::settings::PendingRebind;
+use crate::*;
+
+pub struct MenuPlugin;
+
+#[derive(Component)]
+pub struct Focused;
+#[derive(Event, Debug, Clone, Copy)]
+pub struct MenuButtonPressed(pub Entity);
+#[derive(Component)]
+pub struct BackButton;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<MenuButtonPressed>().add_systems(
+            Update,
+            (
+                focus_hovered_buttons,
+                navigate_menu_focus,
+                send_menu_button_presses,
+                update_menu_button_colors,
+            )
+                .chain()
+                // Keys pressed while rebinding a control only go to the new binding.
+                .run_if(|pending: Res<PendingRebind>| pending.0.is_none()),
+        );
+    }
+}
+
+pub fn spawn_menu_screen(commands: &mut Commands, title: &str) -> Entity {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(20.0),
+                ..default()
+            },
+            background_color: BackgroundColor(OVERLAY_COLOR),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                title,
+                TextStyle {
+                    font_size: 60.0,
+                    color: Color::BLACK,
+                    ..Default::default()
+                },
+            ));
+        })
+        .id()
+}
+
+pub fn spawn_menu_button(parent: &mut ChildBuilder, label: &str, button: impl Bundle) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    min_width: Val::Px(400.0),
+                    height: Val::Px(65.0),
+                    padding: UiRect::horizontal(Val::Px(20.0)),
+                    border: UiRect::all(Val::Px(5.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                border_color: BorderColor(Color::BLACK),
+                image: UiImage::default().with_color(BUTTON_COLOR),
+                ..default()
+            },
+            button,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                label,
+                TextStyle {
+                    font_size: 40.0,
+                    color: Color::BLACK,
+                    ..Default::default()
+                },
+            ));
+        });
+}
+
+// Moving the mouse over a button takes the focus, so keyboard navigation continues from there.
+fn focus_hovered_buttons(
+    mut commands: Commands,
+    hovered_query: Query<(Entity, &Interaction, Has<Focused>), Changed<Interaction>>,
+    focused_query: Query<Entity, With<Focused>>,
+) {
+    for (entity, interaction, focused) in hovered_query.iter() {
+        if focused || *interaction != Interaction::Hovered {
+            continue;
+        }
+
+        for focused in focused_query.iter() {
+            commands.entity(focused).remove::<Focused>();
+        }
+        commands.entity(entity).insert(Focused);
+    }
+}
+
+fn navigate_menu_focus(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepad_input: Res<ButtonInput<GamepadButton>>,
+    button_query: Query<(Entity, &GlobalTransform, Has<Focused>), With<Button>>,
+) {
+    let gamepad_pressed = |button_type: GamepadButtonType| {
+        gamepad_input
+            .get_just_pressed()
+            .any(|button| button.button_type == button_type)
+    };
+    // UI coordinates grow downward.
+    let direction = if keyboard_input.just_pressed(KeyCode::ArrowUp)
+        || gamepad_pressed(GamepadButtonType::DPadUp)
+    {
+        Vec2::NEG_Y
+    } else if keyboard_input.just_pressed(KeyCode::ArrowDown)
+        || gamepad_pressed(GamepadButtonType::DPadDown)
+    {
+        Vec2::Y
+    } else if keyboard_input.just_pressed(KeyCode::ArrowLeft)
+        || gamepad_pressed(GamepadButtonType::DPadLeft)
+    {
+        Vec2::NEG_X
+    } else if keyboard_input.just_pressed(KeyCode::ArrowRight)
+        || gamepad_pressed(GamepadButtonType::DPadRight)
+    {
+        Vec2::X
+    } else {
+        return;
+    };
+
+    let buttons: Vec<(Entity, Vec2, bool)> = button_query
+        .iter()
+        .map(|(entity, transform, focused)| (entity, transform.translation().truncate(), focused))
+        .collect();
+    let Some(&(current, current_pos, _)) = buttons.iter().find(|(_, _, focused)| *focused) else {
+        // Nothing focused yet, start from the top left button.
+        if let Some((first, _, _)) = buttons
+            .iter()
+            .min_by(|a, b| (a.1.y, a.1.x).partial_cmp(&(b.1.y, b.1.x)).unwrap())
+        {
+            commands.entity(*first).insert(Focused);
+        }
+        return;
+    };
+
+    // Closest button in that direction, buttons off to the side count as further away.
+    let score = |pos: Vec2| {
+        let offset = pos - current_pos;
+        let along = offset.dot(direction);
+        let across = (offset - direction * along).length();
+        (along > 0.0).then_some(along + across * 2.0)
+    };
+    let next = buttons
+        .iter()
+        .filter_map(|(entity, pos, _)| score(*pos).map(|score| (*entity, score)))
+        .min_by(|a, b| a.1.total_cmp(&b.1));
+    if let Some((next, _)) = next {
+        commands.entity(current).remove::<Focused>();
+        commands.entity(next).insert(Focused);
+    }
+}
+
+fn send_menu_button_presses(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepad_input: Res<ButtonInput<GamepadButton>>,
+    interaction_query: Query<(Entity, &Interaction), Changed<Interaction>>,
+    focused_query: Query<Entity, With<Focused>>,
+    back_query: Query<Entity, With<BackButton>>,
+    mut pressed_events: EventWriter<MenuButtonPressed>,
+) {
+    let gamepad_pressed = |button_type: GamepadButtonType| {
+        gamepad_input
+            .get_just_pressed()
+            .any(|button| button.button_type == button_type)
+    };
+
+    for (entity, interaction) in interaction_query.iter() {
+        if *interaction == Interaction::Pressed {
+            pressed_events.send(MenuButtonPressed(entity));
+        }
+    }
+
+    if keyboard_input.any_just_pressed([KeyCode::Enter, KeyCode::Space])
+        || gamepad_pressed(GamepadButtonType::South)
+    {
+        for entity in focused_query.iter() {
+            pressed_events.send(MenuButtonPressed(entity));
+        }
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Escape) || gamepad_pressed(GamepadButtonType::East) {
+        for entity in back_query.iter() {
+            pressed_events.send(MenuButtonPressed(entity));
+        }
+    }
+}
+
+fn update_menu_button_colors(
+    mut button_query: Query<(&Interaction, Has<Focused>, &mut UiImage), With<Button>>,
+) {
+    for (interaction, focused, mut image) in button_query.iter_mut() {
+        image.color = match interaction {
+            Interaction::Pressed => BUTTON_PRESSED_COLOR,
+            Interaction::Hovered => BUTTON_FOCUSED_COLOR,
+            Interaction::None if focused => BUTTON_FOCUSED_COLOR,
+            Interaction::None => BUTTON_COLOR,
+        };
+    }
+}
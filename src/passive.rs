@@ -0,0 +1,195 @@
+use std::f32::consts::PI;
+
+use bevy::math::vec3;
+use bevy::prelude::*;
+use bevy::sprite::{MaterialMesh2dBundle, Mesh2dHandle};
+
+use crate::player::Player;
+use crate::state::PlayState;
+use crate::stats::{Stat, Stats};
+use crate::world::GameEntity;
+use crate::*;
+
+pub struct PassiveWeaponPlugin;
+
+#[derive(Component)]
+pub struct OrbitingBlades {
+    pub count: u32,
+    pub radius: f32,
+    pub angular_speed: f32,
+    pub damage: f32,
+    pub hit_interval: f32,
+    angle: f32,
+}
+#[derive(Component)]
+pub struct Blade {
+    pub index: u32,
+    pub hit_timer: Timer,
+}
+#[derive(Component)]
+pub struct DamageAura {
+    pub radius: f32,
+    pub damage: f32,
+    pub timer: Timer,
+}
+#[derive(Component)]
+struct AuraPulse(Timer);
+
+impl Plugin for PassiveWeaponPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                sync_blades,
+                update_blade_transform,
+                tick_blade_timers,
+                tick_aura_timer,
+                spawn_aura_pulse,
+                fade_aura_pulses,
+            )
+                .run_if(in_state(PlayState::Running)),
+        );
+    }
+}
+
+impl Default for OrbitingBlades {
+    fn default() -> Self {
+        Self {
+            count: BLADE_COUNT,
+            radius: BLADE_ORBIT_RADIUS,
+            angular_speed: BLADE_ANGULAR_SPEED,
+            damage: BLADE_DAMAGE,
+            hit_interval: BLADE_HIT_INTERVAL,
+            angle: 0.0,
+        }
+    }
+}
+
+impl Default for DamageAura {
+    fn default() -> Self {
+        Self {
+            radius: AURA_RADIUS,
+            damage: AURA_DAMAGE,
+            timer: Timer::from_seconds(AURA_PULSE_INTERVAL, TimerMode::Repeating),
+        }
+    }
+}
+
+fn sync_blades(
+    mut commands: Commands,
+    handle: Res<GlobalTextureAtlas>,
+    player_query: Query<&OrbitingBlades, (With<Player>, Changed<OrbitingBlades>)>,
+    blade_query: Query<Entity, With<Blade>>,
+) {
+    let Ok(blades) = player_query.get_single() else {
+        return;
+    };
+
+    if blade_query.iter().len() == blades.count as usize {
+        return;
+    }
+
+    for entity in blade_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    for index in 0..blades.count {
+        commands.spawn((
+            SpriteBundle {
+                transform: Transform::from_translation(vec3(0.0, 0.0, 11.0))
+                    .with_scale(Vec3::splat(SPRITE_SCALE_FACTOR)),
+                texture: handle.image.clone().unwrap(),
+                ..default()
+            },
+            TextureAtlas {
+                layout: handle.layout.clone().unwrap(),
+                index: BLADE_SPRITE_INDEX,
+            },
+            Blade {
+                index,
+                hit_timer: Timer::from_seconds(blades.hit_interval, TimerMode::Repeating),
+            },
+            GameEntity,
+        ));
+    }
+}
+
+fn update_blade_transform(
+    time: Res<Time>,
+    mut player_query: Query<(&Transform, &mut OrbitingBlades), With<Player>>,
+    mut blade_query: Query<(&mut Transform, &Blade), Without<Player>>,
+) {
+    if player_query.is_empty() {
+        return;
+    }
+
+    let (player_transform, mut blades) = player_query.single_mut();
+    blades.angle =
+        (blades.angle + blades.angular_speed.to_radians() * time.delta_seconds()) % (2.0 * PI);
+
+    let player_pos = player_transform.translation.truncate();
+    let spacing = 2.0 * PI / blades.count.max(1) as f32;
+    for (mut transform, blade) in blade_query.iter_mut() {
+        let angle = blades.angle + spacing * blade.index as f32;
+        let pos = player_pos + Vec2::from_angle(angle) * blades.radius;
+        transform.translation = pos.extend(transform.translation.z);
+        transform.rotation = Quat::from_rotation_z(angle);
+    }
+}
+
+fn tick_blade_timers(time: Res<Time>, mut blade_query: Query<&mut Blade>) {
+    for mut blade in blade_query.iter_mut() {
+        blade.hit_timer.tick(time.delta());
+    }
+}
+
+fn tick_aura_timer(time: Res<Time>, mut player_query: Query<&mut DamageAura, With<Player>>) {
+    for mut aura in player_query.iter_mut() {
+        aura.timer.tick(time.delta());
+    }
+}
+
+fn spawn_aura_pulse(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    player_query: Query<(&Transform, &DamageAura, &Stats), With<Player>>,
+) {
+    if player_query.is_empty() {
+        return;
+    }
+
+    let (transform, aura, stats) = player_query.single();
+    if !aura.timer.just_finished() {
+        return;
+    }
+
+    commands.spawn((
+        MaterialMesh2dBundle {
+            mesh: Mesh2dHandle(meshes.add(Circle::new(aura.radius * stats.get(Stat::AuraRadius)))),
+            material: materials.add(AURA_COLOR),
+            transform: Transform::from_translation(transform.translation.truncate().extend(5.0)),
+            ..default()
+        },
+        AuraPulse(Timer::from_seconds(AURA_PULSE_FADE_SECS, TimerMode::Once)),
+        GameEntity,
+    ));
+}
+
+fn fade_aura_pulses(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut pulse_query: Query<(&mut AuraPulse, &Handle<ColorMaterial>, Entity)>,
+) {
+    for (mut pulse, material, entity) in pulse_query.iter_mut() {
+        if pulse.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        if let Some(material) = materials.get_mut(material) {
+            material.color =
+                AURA_COLOR.with_alpha(AURA_COLOR.alpha() * pulse.0.fraction_remaining());
+        }
+    }
+}
@@ -0,0 +1,257 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use enemy::EnemyKilledEvent;
+use gun::{Ammo, WeaponInventory};
+use player::{Health, Player};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use stats::{Modifier, ModifierSource, Stat, Stats};
+use weapon::{Weapon, WeaponDef};
+use world::GameEntity;
+
+use crate::state::PlayState;
+use crate::*;
+
+pub struct PickupPlugin;
+
+#[derive(Component)]
+pub struct Pickup(pub PickupKind);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PickupKind {
+    Experience(u32),
+    Heal(f32),
+    Ammo,
+    Buff(BuffKind),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BuffKind {
+    FireRate,
+    Speed,
+    Invincibility,
+}
+
+#[derive(Component, Default)]
+pub struct Buffs(pub HashMap<BuffKind, Timer>);
+
+#[derive(Event, Debug, Clone, Copy)]
+pub struct PickupCollectedEvent {
+    pub kind: PickupKind,
+    pub collector: Entity,
+}
+
+impl Plugin for PickupPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PickupCollectedEvent>().add_systems(
+            Update,
+            (
+                spawn_loot,
+                attract_pickups,
+                collect_pickups,
+                apply_pickups,
+                tick_buffs,
+            )
+                .run_if(in_state(PlayState::Running)),
+        );
+    }
+}
+
+impl PickupKind {
+    fn color(&self) -> Color {
+        match self {
+            PickupKind::Experience(_) => XP_GEM_COLOR,
+            PickupKind::Heal(_) => HEAL_PICKUP_COLOR,
+            PickupKind::Ammo => AMMO_PICKUP_COLOR,
+            PickupKind::Buff(_) => BUFF_PICKUP_COLOR,
+        }
+    }
+
+    fn scale(&self) -> f32 {
+        match self {
+            PickupKind::Experience(_) => XP_GEM_SCALE,
+            _ => 1.0,
+        }
+    }
+}
+
+impl BuffKind {
+    pub fn duration(&self) -> f32 {
+        match self {
+            BuffKind::FireRate => FIRE_RATE_BUFF_SECS,
+            BuffKind::Speed => SPEED_BUFF_SECS,
+            BuffKind::Invincibility => INVINCIBILITY_BUFF_SECS,
+        }
+    }
+
+    pub fn modifier(&self) -> Option<(Stat, Modifier)> {
+        match self {
+            BuffKind::FireRate => Some((
+                Stat::FireRate,
+                Modifier::Multiply(FIRE_RATE_BUFF_MULTIPLIER),
+            )),
+            BuffKind::Speed => Some((Stat::MoveSpeed, Modifier::Multiply(SPEED_BUFF_MULTIPLIER))),
+            BuffKind::Invincibility => None,
+        }
+    }
+}
+
+impl Buffs {
+    pub fn is_active(&self, kind: BuffKind) -> bool {
+        self.0.contains_key(&kind)
+    }
+
+    pub fn add(&mut self, kind: BuffKind, stats: &mut Stats) {
+        self.0
+            .insert(kind, Timer::from_seconds(kind.duration(), TimerMode::Once));
+
+        let source = ModifierSource::Buff(kind);
+        stats.remove_modifiers(source);
+        if let Some((stat, modifier)) = kind.modifier() {
+            stats.add_modifier(stat, source, modifier);
+        }
+    }
+}
+
+pub fn spawn_pickup(
+    commands: &mut Commands,
+    handle: &GlobalTextureAtlas,
+    kind: PickupKind,
+    pos: Vec2,
+) -> Entity {
+    commands
+        .spawn((
+            SpriteBundle {
+                transform: Transform::from_translation(pos.extend(0.5))
+                    .with_scale(Vec3::splat(SPRITE_SCALE_FACTOR * kind.scale())),
+                texture: handle.image.clone().unwrap(),
+                sprite: Sprite {
+                    color: kind.color(),
+                    ..default()
+                },
+                ..default()
+            },
+            TextureAtlas {
+                layout: handle.layout.clone().unwrap(),
+                index: PICKUP_SPRITE_INDEX,
+            },
+            Pickup(kind),
+            GameEntity,
+        ))
+        .id()
+}
+
+fn spawn_loot(
+    mut commands: Commands,
+    handle: Res<GlobalTextureAtlas>,
+    mut killed_events: EventReader<EnemyKilledEvent>,
+) {
+    let loot = [
+        PickupKind::Heal(HEAL_PICKUP_AMOUNT),
+        PickupKind::Ammo,
+        PickupKind::Buff(BuffKind::FireRate),
+        PickupKind::Buff(BuffKind::Speed),
+        PickupKind::Buff(BuffKind::Invincibility),
+    ];
+
+    let mut rng = rand::thread_rng();
+    for event in killed_events.read() {
+        if !rng.gen_bool(LOOT_DROP_CHANCE) {
+            continue;
+        }
+        if let Some(kind) = loot.choose(&mut rng) {
+            spawn_pickup(&mut commands, &handle, *kind, event.position);
+        }
+    }
+}
+
+fn attract_pickups(
+    time: Res<Time>,
+    player_query: Query<&Transform, With<Player>>,
+    mut pickup_query: Query<&mut Transform, (With<Pickup>, Without<Player>)>,
+) {
+    if player_query.is_empty() || pickup_query.is_empty() {
+        return;
+    }
+
+    let player_pos = player_query.single().translation.truncate();
+    for mut transform in pickup_query.iter_mut() {
+        let offset = player_pos - transform.translation.truncate();
+        if offset.length() > PICKUP_MAGNET_RADIUS {
+            continue;
+        }
+
+        let step = (PICKUP_SPEED * time.delta_seconds()).min(offset.length());
+        transform.translation += (offset.normalize_or_zero() * step).extend(0.0);
+    }
+}
+
+fn collect_pickups(
+    mut commands: Commands,
+    player_query: Query<(&Transform, Entity), With<Player>>,
+    pickup_query: Query<(&Transform, &Pickup, Entity), Without<Player>>,
+    mut collected_events: EventWriter<PickupCollectedEvent>,
+) {
+    if player_query.is_empty() || pickup_query.is_empty() {
+        return;
+    }
+
+    let (player_transform, player_entity) = player_query.single();
+    let player_pos = player_transform.translation.truncate();
+    for (transform, pickup, entity) in pickup_query.iter() {
+        if transform.translation.truncate().distance(player_pos) > PICKUP_RADIUS {
+            continue;
+        }
+
+        collected_events.send(PickupCollectedEvent {
+            kind: pickup.0,
+            collector: player_entity,
+        });
+        commands.entity(entity).despawn();
+    }
+}
+
+fn apply_pickups(
+    mut collected_events: EventReader<PickupCollectedEvent>,
+    mut player_query: Query<(&mut Health, &mut Buffs, &mut Stats, &WeaponInventory), With<Player>>,
+    mut gun_query: Query<(&mut Ammo, &Weapon)>,
+    weapon_defs: Res<Assets<WeaponDef>>,
+) {
+    for event in collected_events.read() {
+        let Ok((mut health, mut buffs, mut stats, inventory)) =
+            player_query.get_mut(event.collector)
+        else {
+            continue;
+        };
+
+        match event.kind {
+            PickupKind::Heal(amount) => {
+                health.0 = (health.0 + amount).min(stats.get(Stat::MaxHealth))
+            }
+            PickupKind::Ammo => {
+                for gun in inventory.slots.iter() {
+                    let Ok((mut ammo, weapon)) = gun_query.get_mut(*gun) else {
+                        continue;
+                    };
+                    if let Some(weapon) = weapon_defs.get(&weapon.0) {
+                        ammo.reserve = ammo.reserve.max(weapon.max_reserve_ammo);
+                    }
+                }
+            }
+            PickupKind::Buff(kind) => buffs.add(kind, &mut stats),
+            PickupKind::Experience(_) => (),
+        }
+    }
+}
+
+fn tick_buffs(time: Res<Time>, mut player_query: Query<(&mut Buffs, &mut Stats), With<Player>>) {
+    for (mut buffs, mut stats) in player_query.iter_mut() {
+        buffs.0.retain(|kind, timer| {
+            if !timer.tick(time.delta()).finished() {
+                return true;
+            }
+            stats.remove_modifiers(ModifierSource::Buff(*kind));
+            false
+        });
+    }
+}
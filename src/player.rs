@@ -1,7 +1,9 @@
 use bevy::prelude::*;
 
-use crate::state::GameState;
-use crate::*;
+use crate::dash::Dashing;
+use crate::input::ActionState;
+use crate::state::{GameState, PlayState};
+use crate::stats::{Stat, Stats};
 
 pub struct PlayerPlugin;
 
@@ -15,19 +17,23 @@ pub enum PlayerState {
     Idle,
     Moving,
 }
-#[derive(Event)]
-pub struct PlayerEnemyCollisionEvent();
+
+type SteerablePlayerQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut Transform,
+        &'static mut PlayerState,
+        &'static Stats,
+    ),
+    (With<Player>, Without<Dashing>),
+>;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<PlayerEnemyCollisionEvent>().add_systems(
+        app.add_systems(
             Update,
-            (
-                handle_player_death,
-                handle_player_input,
-                handle_player_enemy_collision_events,
-            )
-                .run_if(in_state(GameState::InGame)),
+            (handle_player_death, handle_player_input).run_if(in_state(PlayState::Running)),
         );
     }
 }
@@ -42,63 +48,27 @@ fn handle_player_death(
 
     let health = player_query.single();
     if health.0 <= 0.0 {
-        next_state.set(GameState::MainMenu);
-    }
-}
-
-fn handle_player_enemy_collision_events(
-    mut player_query: Query<&mut Health, With<Player>>,
-    mut events: EventReader<PlayerEnemyCollisionEvent>,
-) {
-    if player_query.is_empty() {
-        return;
-    }
-
-    let mut health = player_query.single_mut();
-    for _ in events.read() {
-        health.0 -= ENEMY_DAMAGE;
+        next_state.set(GameState::GameOver);
     }
 }
 
 fn handle_player_input(
-    mut player_query: Query<(&mut Transform, &mut PlayerState), With<Player>>,
-    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut player_query: SteerablePlayerQuery,
+    actions: Res<ActionState>,
     time: Res<Time>,
 ) {
     if player_query.is_empty() {
         return;
     }
 
-    let (mut transform, mut player_state) = player_query.single_mut();
-    let w_key: bool =
-        keyboard_input.pressed(KeyCode::KeyW) || keyboard_input.pressed(KeyCode::ArrowUp);
-    let s_key: bool =
-        keyboard_input.pressed(KeyCode::KeyS) || keyboard_input.pressed(KeyCode::ArrowDown);
-    let a_key: bool =
-        keyboard_input.pressed(KeyCode::KeyA) || keyboard_input.pressed(KeyCode::ArrowLeft);
-    let d_key: bool =
-        keyboard_input.pressed(KeyCode::KeyD) || keyboard_input.pressed(KeyCode::ArrowRight);
-
-    let mut delta: Vec2 = Vec2::ZERO;
-    if w_key {
-        delta.y += 1.0;
-    }
-    if s_key {
-        delta.y -= 1.0;
-    }
-    if a_key {
-        delta.x -= 1.0;
-    }
-    if d_key {
-        delta.x += 1.0;
-    }
-    delta = delta.normalize_or_zero();
+    let (mut transform, mut player_state, stats) = player_query.single_mut();
+    let delta = actions.movement;
 
     transform.translation += Vec3 {
         x: delta.x,
         y: delta.y,
         z: 0.0,
-    } * PLAYER_SPEED
+    } * stats.get(Stat::MoveSpeed)
         * time.delta_seconds();
 
     if delta != Vec2::ZERO {
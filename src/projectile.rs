@@ -0,0 +1,83 @@
+use bevy::prelude::*;
+
+use crate::state::PlayState;
+use crate::world::GameEntity;
+use crate::*;
+
+pub struct EnemyProjectilePlugin;
+
+#[derive(Component)]
+pub struct EnemyProjectile {
+    pub damage: f32,
+    pub source: Entity,
+    pub velocity: Vec2,
+    pub lifetime: Timer,
+}
+
+impl Plugin for EnemyProjectilePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (update_enemy_projectiles, despawn_old_enemy_projectiles)
+                .run_if(in_state(PlayState::Running)),
+        );
+    }
+}
+
+pub fn spawn_enemy_projectile(
+    commands: &mut Commands,
+    handle: &GlobalTextureAtlas,
+    pos: Vec2,
+    velocity: Vec2,
+    damage: f32,
+    lifetime: f32,
+    source: Entity,
+) -> Entity {
+    commands
+        .spawn((
+            SpriteBundle {
+                transform: Transform::from_translation(pos.extend(2.0))
+                    .with_rotation(Quat::from_rotation_z(velocity.to_angle()))
+                    .with_scale(Vec3::splat(SPRITE_SCALE_FACTOR)),
+                texture: handle.image.clone().unwrap(),
+                sprite: Sprite {
+                    color: ENEMY_PROJECTILE_COLOR,
+                    ..default()
+                },
+                ..default()
+            },
+            TextureAtlas {
+                layout: handle.layout.clone().unwrap(),
+                index: ENEMY_PROJECTILE_SPRITE_INDEX,
+            },
+            EnemyProjectile {
+                damage,
+                source,
+                velocity,
+                lifetime: Timer::from_seconds(lifetime, TimerMode::Once),
+            },
+            GameEntity,
+        ))
+        .id()
+}
+
+fn update_enemy_projectiles(
+    time: Res<Time>,
+    mut projectile_query: Query<(&mut Transform, &EnemyProjectile)>,
+) {
+    for (mut transform, projectile) in projectile_query.iter_mut() {
+        transform.translation += (projectile.velocity * time.delta_seconds()).extend(0.0);
+    }
+}
+
+fn despawn_old_enemy_projectiles(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut projectile_query: Query<(&mut EnemyProjectile, Entity)>,
+) {
+    for (mut projectile, entity) in projectile_query.iter_mut() {
+        if projectile.lifetime.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
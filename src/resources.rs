@@ -1,5 +1,16 @@
+use std::marker::PhantomData;
+
+use bevy::asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext};
+use bevy::asset::{LoadedFolder, RecursiveDependencyLoadState};
 use bevy::{prelude::*, window::PrimaryWindow};
+use enemy::EnemyDef;
+use input::ActionState;
+use player::Player;
+use serde::Deserialize;
 use state::GameState;
+use thiserror::Error;
+use wave::WaveSchedule;
+use weapon::WeaponDef;
 
 use crate::*;
 
@@ -12,14 +23,46 @@ pub struct GlobalTextureAtlas {
     pub image: Option<Handle<Image>>,
 }
 
+#[derive(Resource)]
+pub struct AssetLibrary<A: DefinitionAsset> {
+    pub folder: Option<Handle<LoadedFolder>>,
+    pub assets: Vec<Handle<A>>,
+}
+pub type WeaponLibrary = AssetLibrary<WeaponDef>;
+pub type EnemyLibrary = AssetLibrary<EnemyDef>;
+pub type WaveLibrary = AssetLibrary<WaveSchedule>;
+
+pub trait DefinitionAsset: Asset + for<'de> Deserialize<'de> {
+    const EXTENSIONS: &'static [&'static str];
+    fn name(&self) -> &str;
+}
+
+pub struct RonAssetLoader<A>(PhantomData<fn() -> A>);
+
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum RonAssetLoaderError {
+    #[error("Could not read definition: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse definition: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+}
+
 #[derive(Resource)]
 pub struct CursorPosition(pub Option<Vec2>);
 
 impl Plugin for ResourcesPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(GlobalTextureAtlas::default())
+            .insert_resource(WeaponLibrary::default())
+            .insert_resource(EnemyLibrary::default())
+            .insert_resource(WaveLibrary::default())
             .insert_resource(CursorPosition(None))
             .add_systems(OnEnter(GameState::Loading), load_assets)
+            .add_systems(
+                Update,
+                (check_assets_loaded).run_if(in_state(GameState::Loading)),
+            )
             .add_systems(
                 Update,
                 (update_cursor_position).run_if(in_state(GameState::InGame)),
@@ -29,9 +72,11 @@ impl Plugin for ResourcesPlugin {
 
 fn load_assets(
     mut handle: ResMut<GlobalTextureAtlas>,
+    mut weapon_library: ResMut<WeaponLibrary>,
+    mut enemy_library: ResMut<EnemyLibrary>,
+    mut wave_library: ResMut<WaveLibrary>,
     asset_server: Res<AssetServer>,
     mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
-    mut next_state: ResMut<NextState<GameState>>,
 ) {
     handle.image = Some(asset_server.load(SPRITE_SHEET_PATH));
     let layout = TextureAtlasLayout::from_grid(
@@ -43,16 +88,44 @@ fn load_assets(
     );
     handle.layout = Some(texture_atlas_layouts.add(layout));
 
-    next_state.set(GameState::MainMenu);
+    weapon_library.folder = Some(asset_server.load_folder(WEAPONS_PATH));
+    enemy_library.folder = Some(asset_server.load_folder(ENEMIES_PATH));
+    wave_library.folder = Some(asset_server.load_folder(WAVES_PATH));
+}
+
+fn check_assets_loaded(
+    asset_server: Res<AssetServer>,
+    mut weapon_library: ResMut<WeaponLibrary>,
+    mut enemy_library: ResMut<EnemyLibrary>,
+    mut wave_library: ResMut<WaveLibrary>,
+    folders: Res<Assets<LoadedFolder>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let weapons_loaded = weapon_library.collect(&asset_server, &folders);
+    let enemies_loaded = enemy_library.collect(&asset_server, &folders);
+    let waves_loaded = wave_library.collect(&asset_server, &folders);
+
+    if weapons_loaded && enemies_loaded && waves_loaded {
+        next_state.set(GameState::MainMenu);
+    }
 }
 
 fn update_cursor_position(
     mut cursor_pos: ResMut<CursorPosition>,
+    actions: Res<ActionState>,
+    player_query: Query<&Transform, With<Player>>,
     window_query: Query<&Window, With<PrimaryWindow>>,
     camera_query: Query<(&Camera, &GlobalTransform), With<Camera>>,
 ) {
+    // Twin-stick aiming puts the cursor at a fixed distance from the player.
+    if let (Some(aim), Ok(player_transform)) = (actions.aim, player_query.get_single()) {
+        cursor_pos.0 = Some(player_transform.translation.truncate() + aim * GAMEPAD_AIM_DISTANCE);
+        return;
+    }
+
     if window_query.is_empty() || camera_query.is_empty() {
         cursor_pos.0 = None;
+        return;
     }
 
     let (camera, camera_transform) = camera_query.single();
@@ -63,6 +136,74 @@ fn update_cursor_position(
         .map(|ray| ray.origin.truncate());
 }
 
+impl<A: DefinitionAsset> AssetLibrary<A> {
+    pub fn get(&self, name: &str, defs: &Assets<A>) -> Option<Handle<A>> {
+        self.assets
+            .iter()
+            .find(|handle| defs.get(*handle).is_some_and(|def| def.name() == name))
+            .cloned()
+    }
+
+    fn collect(&mut self, asset_server: &AssetServer, folders: &Assets<LoadedFolder>) -> bool {
+        let Some(folder) = self.folder.clone() else {
+            return false;
+        };
+
+        match asset_server.recursive_dependency_load_state(&folder) {
+            RecursiveDependencyLoadState::Loaded => (),
+            RecursiveDependencyLoadState::Failed => {
+                error!("Some definitions in {:?} failed to load", folder.path())
+            }
+            _ => return false,
+        }
+
+        if let Some(folder) = folders.get(&folder) {
+            self.assets = folder
+                .handles
+                .iter()
+                .filter_map(|handle| handle.clone().try_typed::<A>().ok())
+                .collect();
+        }
+        true
+    }
+}
+
+impl<A: DefinitionAsset> Default for AssetLibrary<A> {
+    fn default() -> Self {
+        Self {
+            folder: None,
+            assets: Vec::new(),
+        }
+    }
+}
+
+impl<A> Default for RonAssetLoader<A> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<A: DefinitionAsset> AssetLoader for RonAssetLoader<A> {
+    type Asset = A;
+    type Settings = ();
+    type Error = RonAssetLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes::<A>(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        A::EXTENSIONS
+    }
+}
+
 impl Default for GlobalTextureAtlas {
     fn default() -> Self {
         Self {
@@ -0,0 +1,330 @@
+use std::fs;
+use std::path::PathBuf;
+
+use bevy::{
+    prelude::*,
+    window::{PresentMode, PrimaryWindow, WindowMode, WindowResolution},
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::input::{Action, InputBinding, InputMap};
+use crate::*;
+
+pub struct SettingsPlugin;
+
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct Settings {
+    pub video: VideoSettings,
+    pub audio: AudioSettings,
+    pub controls: InputMap,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct VideoSettings {
+    pub window_mode: WindowMode,
+    pub resolution: (u32, u32),
+    pub vsync: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct AudioSettings {
+    pub master: f32,
+    pub music: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VolumeChannel {
+    Master,
+    Music,
+}
+
+#[derive(Resource, Default)]
+pub struct PendingRebind(pub Option<Action>);
+
+#[derive(Resource, Default)]
+pub struct SettingsLoadError(pub Option<SettingsError>);
+
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum SettingsError {
+    #[error("No config directory was found for the settings file")]
+    NoConfigDir,
+    #[error("Could not access settings: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse settings: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+    #[error("Could not write settings: {0}")]
+    Write(#[from] ron::Error),
+}
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>()
+            .init_resource::<PendingRebind>()
+            .init_resource::<SettingsLoadError>()
+            .add_systems(Startup, report_settings_load_error)
+            .add_systems(
+                Update,
+                (
+                    apply_video_settings.run_if(resource_changed::<Settings>),
+                    // The settings were just loaded on the first frame, there is nothing new to save.
+                    save_settings.run_if(
+                        resource_changed::<Settings>.and_then(not(resource_added::<Settings>)),
+                    ),
+                ),
+            )
+            .add_systems(
+                Update,
+                capture_binding.run_if(|pending: Res<PendingRebind>| pending.0.is_some()),
+            );
+    }
+}
+
+impl Default for VideoSettings {
+    fn default() -> Self {
+        Self {
+            window_mode: WindowMode::BorderlessFullscreen,
+            resolution: RESOLUTIONS[0],
+            vsync: true,
+        }
+    }
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            master: 1.0,
+            music: 1.0,
+        }
+    }
+}
+
+impl Settings {
+    pub fn path() -> Result<PathBuf, SettingsError> {
+        dirs::config_dir()
+            .map(|dir| dir.join(SETTINGS_DIR).join(SETTINGS_FILE))
+            .ok_or(SettingsError::NoConfigDir)
+    }
+
+    pub fn load() -> Result<Self, SettingsError> {
+        let path = Self::path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let mut settings: Self = ron::from_str(&fs::read_to_string(path)?)?;
+        settings.controls.add_missing_defaults();
+        Ok(settings)
+    }
+
+    pub fn save(&self) -> Result<(), SettingsError> {
+        let path = Self::path()?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(
+            path,
+            ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?,
+        )?;
+        Ok(())
+    }
+
+    pub fn window(&self) -> Window {
+        let mut window = Window {
+            resizable: false,
+            focused: true,
+            ..default()
+        };
+        self.video.apply(&mut window);
+        window
+    }
+}
+
+impl VideoSettings {
+    pub fn apply(&self, window: &mut Window) {
+        let (width, height) = self.resolution;
+        window.mode = self.window_mode;
+        window.resolution = WindowResolution::new(width as f32, height as f32);
+        window.present_mode = if self.vsync {
+            PresentMode::AutoVsync
+        } else {
+            PresentMode::AutoNoVsync
+        };
+    }
+
+    pub fn next_window_mode(&mut self) {
+        self.window_mode = match self.window_mode {
+            WindowMode::Windowed => WindowMode::BorderlessFullscreen,
+            WindowMode::BorderlessFullscreen => WindowMode::Fullscreen,
+            _ => WindowMode::Windowed,
+        };
+    }
+
+    pub fn next_resolution(&mut self) {
+        let index = RESOLUTIONS
+            .iter()
+            .position(|resolution| *resolution == self.resolution)
+            .map_or(0, |index| (index + 1) % RESOLUTIONS.len());
+        self.resolution = RESOLUTIONS[index];
+    }
+}
+
+impl AudioSettings {
+    pub fn get(&self, channel: VolumeChannel) -> f32 {
+        match channel {
+            VolumeChannel::Master => self.master,
+            VolumeChannel::Music => self.music,
+        }
+    }
+
+    pub fn step(&mut self, channel: VolumeChannel) {
+        let volume = match channel {
+            VolumeChannel::Master => &mut self.master,
+            VolumeChannel::Music => &mut self.music,
+        };
+        *volume = if *volume >= 1.0 - VOLUME_STEP / 2.0 {
+            0.0
+        } else {
+            (*volume + VOLUME_STEP).min(1.0)
+        };
+    }
+
+    pub fn music_volume(&self) -> f32 {
+        self.master * self.music
+    }
+}
+
+// Volume and binding changes leave the window alone, it is only touched when the video settings differ.
+fn apply_video_settings(
+    settings: Res<Settings>,
+    mut applied: Local<Option<VideoSettings>>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if *applied == Some(settings.video) {
+        return;
+    }
+    if let Ok(mut window) = window_query.get_single_mut() {
+        settings.video.apply(&mut window);
+        *applied = Some(settings.video);
+    }
+}
+
+fn report_settings_load_error(mut load_error: ResMut<SettingsLoadError>) {
+    if let Some(error) = load_error.0.take() {
+        warn!("{error}, using the default settings");
+    }
+}
+
+fn save_settings(settings: Res<Settings>) {
+    if let Err(error) = settings.save() {
+        warn!("{error}");
+    }
+}
+
+fn capture_binding(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mouse_button_input: Res<ButtonInput<MouseButton>>,
+    gamepad_input: Res<ButtonInput<GamepadButton>>,
+    mut pending: ResMut<PendingRebind>,
+    mut settings: ResMut<Settings>,
+) {
+    // The button that confirmed the rebind button is still pressed on the frame the prompt opens.
+    if pending.is_changed() {
+        return;
+    }
+    let Some(action) = pending.0 else {
+        return;
+    };
+    let binding = keyboard_input
+        .get_just_pressed()
+        .map(|key| InputBinding::Key(*key))
+        .chain(
+            mouse_button_input
+                .get_just_pressed()
+                .map(|button| InputBinding::Mouse(*button)),
+        )
+        .chain(
+            gamepad_input
+                .get_just_pressed()
+                .map(|button| InputBinding::Gamepad(button.button_type)),
+        )
+        .next();
+    let Some(binding) = binding else {
+        return;
+    };
+
+    settings.controls.rebind(action, binding);
+    pending.0 = None;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_survive_a_round_trip() {
+        let mut settings = Settings::default();
+        settings.video.window_mode = WindowMode::Windowed;
+        settings.audio.music = 0.5;
+        settings
+            .controls
+            .rebind(Action::Dash, InputBinding::Key(KeyCode::KeyF));
+
+        let text =
+            ron::ser::to_string_pretty(&settings, ron::ser::PrettyConfig::default()).unwrap();
+
+        assert_eq!(ron::from_str::<Settings>(&text).unwrap(), settings);
+    }
+
+    #[test]
+    fn missing_fields_use_the_defaults() {
+        let settings: Settings = ron::from_str("(audio: (music: 0.3))").unwrap();
+
+        assert_eq!(settings.audio.music, 0.3);
+        assert_eq!(settings.audio.master, 1.0);
+        assert_eq!(settings.video, VideoSettings::default());
+        assert_eq!(settings.controls, InputMap::default());
+    }
+
+    #[test]
+    fn window_is_only_touched_when_the_video_settings_change() {
+        let mut world = World::new();
+        world.insert_resource(Settings::default());
+        let window = world.spawn((Window::default(), PrimaryWindow)).id();
+        let system = world.register_system(apply_video_settings);
+        let window_changed = |world: &World| {
+            world
+                .entity(window)
+                .get_ref::<Window>()
+                .unwrap()
+                .is_changed()
+        };
+
+        world.run_system(system).unwrap();
+        world.clear_trackers();
+        world.resource_mut::<Settings>().audio.music = 0.5;
+        world.run_system(system).unwrap();
+        assert!(!window_changed(&world));
+
+        world.clear_trackers();
+        world.resource_mut::<Settings>().video.next_resolution();
+        world.run_system(system).unwrap();
+        assert!(window_changed(&world));
+    }
+
+    #[test]
+    fn volume_wraps_to_silent_past_the_maximum() {
+        let mut audio = AudioSettings {
+            music: 0.9,
+            ..default()
+        };
+
+        audio.step(VolumeChannel::Music);
+        assert!((audio.music - 1.0).abs() < 1e-6);
+        audio.step(VolumeChannel::Music);
+        assert_eq!(audio.music, 0.0);
+    }
+}
@@ -1,20 +1,37 @@
-use bevy::prelude::*;
+use bevy::{audio::Volume, prelude::*};
 
-use crate::{state::GameState, MUSIC_PATH};
+use crate::{settings::Settings, state::GameState, MUSIC_PATH};
 
 pub struct SoundPlugin;
 
+#[derive(Component)]
+struct Music;
+
 impl Plugin for SoundPlugin {
     fn build(&self, app: &mut App) {
-            app.add_systems(OnEnter(GameState::Loading), setup_volume);
+        app.add_systems(OnEnter(GameState::Loading), setup_volume)
+            .add_systems(Update, update_music_volume);
     }
 }
 
-fn setup_volume(
-    asset_server: Res<AssetServer>, mut commands: Commands
-){
-    commands.spawn(AudioBundle {
-        source: asset_server.load(MUSIC_PATH),
-        ..default()
-    });
-}
\ No newline at end of file
+fn setup_volume(asset_server: Res<AssetServer>, settings: Res<Settings>, mut commands: Commands) {
+    commands.spawn((
+        AudioBundle {
+            source: asset_server.load(MUSIC_PATH),
+            settings: PlaybackSettings {
+                volume: Volume::new(settings.audio.music_volume()),
+                ..default()
+            },
+        },
+        Music,
+    ));
+}
+
+// The sink only exists once the music starts playing, volume changes made before that are caught up then.
+fn update_music_volume(settings: Res<Settings>, sink_query: Query<Ref<AudioSink>, With<Music>>) {
+    for sink in sink_query.iter() {
+        if settings.is_changed() || sink.is_added() {
+            sink.set_volume(settings.audio.music_volume());
+        }
+    }
+}
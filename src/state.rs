@@ -1,5 +1,9 @@
 use bevy::prelude::*;
 
+use crate::input::{Action, ActionState};
+
+pub struct PlayStatePlugin;
+
 #[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, States)]
 pub enum GameState {
     #[default]
@@ -7,4 +11,87 @@ pub enum GameState {
     MainMenu,
     GameInit,
     InGame,
+    GameOver,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct InRun;
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, SubStates)]
+#[source(GameState = GameState::InGame)]
+pub enum PlayState {
+    #[default]
+    Running,
+    LevelUp,
+    Paused,
+}
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, SubStates)]
+#[source(GameState = GameState::MainMenu)]
+pub enum MainMenuScreen {
+    #[default]
+    Main,
+    Settings,
+    Controls,
+    Credits,
+}
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, SubStates)]
+#[source(PlayState = PlayState::Paused)]
+pub enum PauseMenu {
+    #[default]
+    Main,
+    Settings,
+    Controls,
+}
+
+impl ComputedStates for InRun {
+    type SourceStates = GameState;
+
+    fn compute(state: GameState) -> Option<Self> {
+        matches!(state, GameState::InGame | GameState::GameOver).then_some(InRun)
+    }
+}
+
+impl Plugin for PlayStatePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_computed_state::<InRun>()
+            .add_sub_state::<PlayState>()
+            .add_sub_state::<MainMenuScreen>()
+            .add_sub_state::<PauseMenu>()
+            .add_systems(OnExit(PlayState::Running), pause_game_time)
+            .add_systems(OnEnter(PlayState::Running), resume_game_time)
+            .add_systems(
+                Update,
+                handle_pause_input.run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+// Gameplay timers tick with virtual time, so pausing it freezes them in place.
+fn pause_game_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+fn resume_game_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+// The pause action opens the pause menu and resumes from its main page.
+fn handle_pause_input(
+    actions: Res<ActionState>,
+    play_state: Res<State<PlayState>>,
+    pause_menu: Option<Res<State<PauseMenu>>>,
+    mut next_play_state: ResMut<NextState<PlayState>>,
+) {
+    if !actions.just_pressed(Action::Pause) {
+        return;
+    }
+
+    match (play_state.get(), pause_menu.as_deref().map(State::get)) {
+        (PlayState::Running, _) => next_play_state.set(PlayState::Paused),
+        (PlayState::Paused, Some(PauseMenu::Main)) => next_play_state.set(PlayState::Running),
+        // The settings pages are left through their Back button, which Escape presses.
+        (PlayState::Paused, _) | (PlayState::LevelUp, _) => (),
+    }
 }
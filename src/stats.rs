@@ -0,0 +1,173 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::pickup::BuffKind;
+use crate::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stat {
+    MoveSpeed,
+    MaxHealth,
+    Damage,
+    FireRate,
+    ProjectileSpeed,
+    ReloadSpeed,
+    PassiveDamage,
+    AuraRadius,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Modifier {
+    Add(f32),
+    Multiply(f32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ModifierSource {
+    Upgrade,
+    Buff(BuffKind),
+}
+
+#[derive(Component)]
+pub struct Stats {
+    base: HashMap<Stat, f32>,
+    modifiers: Vec<(Stat, ModifierSource, Modifier)>,
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Self {
+            base: HashMap::from([
+                (Stat::MoveSpeed, PLAYER_SPEED),
+                (Stat::MaxHealth, PLAYER_HEALTH),
+            ]),
+            modifiers: Vec::new(),
+        }
+    }
+}
+
+impl Stat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Stat::MoveSpeed => "Move speed",
+            Stat::MaxHealth => "Max health",
+            Stat::Damage => "Damage",
+            Stat::FireRate => "Fire rate",
+            Stat::ProjectileSpeed => "Projectile speed",
+            Stat::ReloadSpeed => "Reload speed",
+            Stat::PassiveDamage => "Passive damage",
+            Stat::AuraRadius => "Aura radius",
+        }
+    }
+
+    pub fn is_multiplier(&self) -> bool {
+        !matches!(self, Stat::MoveSpeed | Stat::MaxHealth)
+    }
+}
+
+impl Modifier {
+    pub fn describe(&self) -> String {
+        match self {
+            Modifier::Add(value) => format!("{value:+}"),
+            Modifier::Multiply(value) => format!("{:+.0}%", (value - 1.0) * 100.0),
+        }
+    }
+}
+
+impl Stats {
+    pub fn get(&self, stat: Stat) -> f32 {
+        let mut add = 0.0;
+        let mut multiply = 1.0;
+        for (_, _, modifier) in self.modifiers.iter().filter(|(s, ..)| *s == stat) {
+            match modifier {
+                Modifier::Add(value) => add += value,
+                Modifier::Multiply(value) => multiply *= value,
+            }
+        }
+
+        (self.base(stat) + add) * multiply
+    }
+
+    fn base(&self, stat: Stat) -> f32 {
+        self.base.get(&stat).copied().unwrap_or(1.0)
+    }
+
+    pub fn add_modifier(&mut self, stat: Stat, source: ModifierSource, modifier: Modifier) {
+        // Multiplier stats scale a value owned by something else, adding to them has no unit.
+        if stat.is_multiplier() && matches!(modifier, Modifier::Add(_)) {
+            warn!("Ignoring {modifier:?} on {stat:?}, only multipliers apply to it");
+            return;
+        }
+
+        self.modifiers.push((stat, source, modifier));
+    }
+
+    pub fn remove_modifiers(&mut self, source: ModifierSource) {
+        self.modifiers.retain(|(_, s, _)| *s != source);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn additive_modifiers_apply_before_multipliers() {
+        let mut stats = Stats::default();
+        stats.add_modifier(
+            Stat::MoveSpeed,
+            ModifierSource::Upgrade,
+            Modifier::Multiply(2.0),
+        );
+        stats.add_modifier(
+            Stat::MoveSpeed,
+            ModifierSource::Upgrade,
+            Modifier::Add(10.0),
+        );
+        stats.add_modifier(
+            Stat::MoveSpeed,
+            ModifierSource::Upgrade,
+            Modifier::Multiply(1.5),
+        );
+
+        assert_eq!(stats.get(Stat::MoveSpeed), (PLAYER_SPEED + 10.0) * 3.0);
+    }
+
+    #[test]
+    fn modifiers_only_apply_to_their_stat() {
+        let mut stats = Stats::default();
+        stats.add_modifier(
+            Stat::Damage,
+            ModifierSource::Upgrade,
+            Modifier::Multiply(2.0),
+        );
+
+        assert_eq!(stats.get(Stat::Damage), 2.0);
+        assert_eq!(stats.get(Stat::FireRate), 1.0);
+        assert_eq!(stats.get(Stat::MaxHealth), PLAYER_HEALTH);
+    }
+
+    #[test]
+    fn removing_a_source_keeps_the_other_modifiers() {
+        let mut stats = Stats::default();
+        let buff = ModifierSource::Buff(BuffKind::FireRate);
+        stats.add_modifier(
+            Stat::FireRate,
+            ModifierSource::Upgrade,
+            Modifier::Multiply(1.5),
+        );
+        stats.add_modifier(Stat::FireRate, buff, Modifier::Multiply(2.0));
+
+        stats.remove_modifiers(buff);
+
+        assert_eq!(stats.get(Stat::FireRate), 1.5);
+    }
+
+    #[test]
+    fn multiplier_stats_ignore_additive_modifiers() {
+        let mut stats = Stats::default();
+        stats.add_modifier(Stat::FireRate, ModifierSource::Upgrade, Modifier::Add(0.5));
+
+        assert_eq!(stats.get(Stat::FireRate), 1.0);
+    }
+}
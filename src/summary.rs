@@ -0,0 +1,61 @@
+use bevy::prelude::*;
+
+use crate::damage::{DamageEvent, DamageKind};
+use crate::enemy::{Enemy, EnemyKilledEvent};
+use crate::state::{GameState, PlayState};
+
+pub struct RunSummaryPlugin;
+
+#[derive(Resource, Default, Debug, Clone)]
+pub struct RunSummary {
+    pub time: f32,
+    pub kills: u32,
+    pub damage_dealt: f32,
+}
+
+impl Plugin for RunSummaryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RunSummary>()
+            .add_systems(OnEnter(GameState::GameInit), reset_run_summary)
+            .add_systems(
+                Update,
+                (track_survival_time, track_kills, track_damage_dealt)
+                    .run_if(in_state(PlayState::Running)),
+            );
+    }
+}
+
+impl RunSummary {
+    pub fn formatted_time(&self) -> String {
+        let seconds = self.time as u32;
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+fn reset_run_summary(mut summary: ResMut<RunSummary>) {
+    *summary = RunSummary::default();
+}
+
+fn track_survival_time(mut summary: ResMut<RunSummary>, time: Res<Time>) {
+    summary.time += time.delta_seconds();
+}
+
+fn track_kills(mut summary: ResMut<RunSummary>, mut killed_events: EventReader<EnemyKilledEvent>) {
+    summary.kills += killed_events
+        .read()
+        .filter(|e| e.kind != DamageKind::SelfDestruct)
+        .count() as u32;
+}
+
+fn track_damage_dealt(
+    mut summary: ResMut<RunSummary>,
+    mut damage_events: EventReader<DamageEvent>,
+    enemy_query: Query<(), With<Enemy>>,
+) {
+    for event in damage_events.read() {
+        // Exploders going off are not the player's doing.
+        if event.kind != DamageKind::SelfDestruct && enemy_query.contains(event.target) {
+            summary.damage_dealt += event.amount;
+        }
+    }
+}
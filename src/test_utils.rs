@@ -0,0 +1,11 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+pub fn world_with_time(delta: Duration) -> World {
+    let mut world = World::new();
+    let mut time = Time::<()>::default();
+    time.advance_by(delta);
+    world.insert_resource(time);
+    world
+}
@@ -0,0 +1,167 @@
+use bevy::prelude::*;
+use gun::{spawn_gun, WeaponInventory};
+use passive::OrbitingBlades;
+use player::{Health, Player};
+use rand::seq::SliceRandom;
+use stats::{Modifier, ModifierSource, Stat, Stats};
+use weapon::{Weapon, WeaponDef};
+
+use crate::state::PlayState;
+use crate::*;
+
+pub struct UpgradePlugin;
+
+const STAT_UPGRADES: [(Stat, Modifier); 8] = [
+    (Stat::MoveSpeed, Modifier::Multiply(1.1)),
+    (Stat::MaxHealth, Modifier::Add(20.0)),
+    (Stat::Damage, Modifier::Multiply(1.15)),
+    (Stat::FireRate, Modifier::Multiply(1.15)),
+    (Stat::ProjectileSpeed, Modifier::Multiply(1.2)),
+    (Stat::ReloadSpeed, Modifier::Multiply(1.2)),
+    (Stat::PassiveDamage, Modifier::Multiply(1.25)),
+    (Stat::AuraRadius, Modifier::Multiply(1.2)),
+];
+
+#[derive(Debug, Clone)]
+pub enum Upgrade {
+    NewWeapon {
+        weapon: Handle<WeaponDef>,
+        name: String,
+    },
+    ExtraBlade,
+    Stat(Stat, Modifier),
+    Heal,
+}
+
+#[derive(Resource, Default)]
+pub struct UpgradeChoices(pub Vec<Upgrade>);
+
+#[derive(Event, Debug, Clone, Copy)]
+pub struct UpgradeChosenEvent(pub usize);
+
+impl Plugin for UpgradePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<UpgradeChoices>()
+            .add_event::<UpgradeChosenEvent>()
+            .add_systems(OnEnter(PlayState::LevelUp), draw_upgrade_choices)
+            .add_systems(
+                Update,
+                apply_chosen_upgrade.run_if(in_state(PlayState::LevelUp)),
+            );
+    }
+}
+
+impl Upgrade {
+    pub fn title(&self) -> String {
+        match self {
+            Upgrade::NewWeapon { name, .. } => format!("New weapon: {name}"),
+            Upgrade::ExtraBlade => "Extra blade".to_string(),
+            Upgrade::Stat(stat, _) => stat.name().to_string(),
+            Upgrade::Heal => "Heal".to_string(),
+        }
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            Upgrade::NewWeapon { .. } => "Adds the weapon to a free slot".to_string(),
+            Upgrade::ExtraBlade => "One more blade orbits around you".to_string(),
+            Upgrade::Stat(stat, modifier) => format!("{} {}", stat.name(), modifier.describe()),
+            Upgrade::Heal => "Restores all health".to_string(),
+        }
+    }
+}
+
+pub fn draw_upgrade_choices(
+    mut choices: ResMut<UpgradeChoices>,
+    weapon_library: Res<WeaponLibrary>,
+    weapon_defs: Res<Assets<WeaponDef>>,
+    player_query: Query<(&WeaponInventory, &OrbitingBlades), With<Player>>,
+    gun_query: Query<&Weapon>,
+) {
+    choices.0.clear();
+    if player_query.is_empty() {
+        return;
+    }
+
+    let (inventory, blades) = player_query.single();
+    let owned: Vec<&Handle<WeaponDef>> = inventory
+        .slots
+        .iter()
+        .filter_map(|gun| gun_query.get(*gun).ok())
+        .map(|weapon| &weapon.0)
+        .collect();
+
+    let mut pool: Vec<Upgrade> = Vec::new();
+    if inventory.slots.len() < MAX_WEAPON_SLOTS {
+        for weapon in weapon_library.assets.iter() {
+            if owned.contains(&weapon) {
+                continue;
+            }
+            if let Some(def) = weapon_defs.get(weapon) {
+                pool.push(Upgrade::NewWeapon {
+                    weapon: weapon.clone(),
+                    name: def.name.clone(),
+                });
+            }
+        }
+    }
+    if blades.count < MAX_BLADE_COUNT {
+        pool.push(Upgrade::ExtraBlade);
+    }
+    pool.extend(
+        STAT_UPGRADES
+            .iter()
+            .map(|(stat, modifier)| Upgrade::Stat(*stat, *modifier)),
+    );
+    pool.push(Upgrade::Heal);
+
+    pool.shuffle(&mut rand::thread_rng());
+    pool.truncate(UPGRADE_CHOICES);
+    choices.0 = pool;
+}
+
+fn apply_chosen_upgrade(
+    mut commands: Commands,
+    handle: Res<GlobalTextureAtlas>,
+    weapon_defs: Res<Assets<WeaponDef>>,
+    choices: Res<UpgradeChoices>,
+    mut chosen_events: EventReader<UpgradeChosenEvent>,
+    mut player_query: Query<
+        (
+            &mut WeaponInventory,
+            &mut OrbitingBlades,
+            &mut Stats,
+            &mut Health,
+        ),
+        With<Player>,
+    >,
+    mut next_state: ResMut<NextState<PlayState>>,
+) {
+    let Some(event) = chosen_events.read().last() else {
+        return;
+    };
+    if player_query.is_empty() {
+        return;
+    }
+
+    let (mut inventory, mut blades, mut stats, mut health) = player_query.single_mut();
+    match choices.0.get(event.0) {
+        Some(Upgrade::NewWeapon { weapon, .. }) => {
+            if let Some(weapon_def) = weapon_defs.get(weapon) {
+                let gun = spawn_gun(&mut commands, &handle, weapon.clone(), weapon_def);
+                inventory.slots.push(gun);
+            }
+        }
+        Some(Upgrade::ExtraBlade) => blades.count += 1,
+        Some(Upgrade::Stat(stat, modifier)) => {
+            // Raising the maximum health also grants the extra health right away.
+            let max_health = stats.get(Stat::MaxHealth);
+            stats.add_modifier(*stat, ModifierSource::Upgrade, *modifier);
+            health.0 += stats.get(Stat::MaxHealth) - max_health;
+        }
+        Some(Upgrade::Heal) => health.0 = stats.get(Stat::MaxHealth),
+        None => (),
+    }
+
+    next_state.set(PlayState::Running);
+}
@@ -0,0 +1,351 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use boss::spawn_boss;
+use enemy::{spawn_enemy, Enemy, EnemyArchetypes, EnemyDef};
+use player::Player;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::rngs::ThreadRng;
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::state::{GameState, PlayState};
+use crate::*;
+
+pub struct WavePlugin;
+
+#[derive(Asset, TypePath, Deserialize, Debug, Clone, Default)]
+pub struct WaveSchedule {
+    pub name: String,
+    pub waves: Vec<WaveDef>,
+    pub escalation: Escalation,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct WaveDef {
+    pub duration: f32,
+    #[serde(default)]
+    pub break_duration: f32,
+    #[serde(default)]
+    pub mix: HashMap<String, f32>,
+    pub spawn_rate: SpawnRate,
+    pub max_enemies: usize,
+    #[serde(default)]
+    pub boss: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct SpawnRate {
+    pub start: f32,
+    pub end: f32,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct Escalation {
+    pub spawn_rate: f32,
+    pub health: f32,
+    pub max_enemies: f32,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WavePhase {
+    #[default]
+    Active,
+    Break,
+}
+
+#[derive(Resource, Default)]
+pub struct WaveDirector {
+    pub schedule: Option<Handle<WaveSchedule>>,
+    pub wave: u32,
+    pub phase: WavePhase,
+    pub timer: Timer,
+    pending: f32,
+    boss_pending: bool,
+}
+
+impl Plugin for WavePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WaveDirector>()
+            .init_asset::<WaveSchedule>()
+            .init_asset_loader::<RonAssetLoader<WaveSchedule>>()
+            .add_systems(OnEnter(GameState::GameInit), reset_wave_director)
+            .add_systems(
+                Update,
+                (update_wave_director, spawn_wave_enemies, spawn_wave_boss)
+                    .chain()
+                    .run_if(in_state(PlayState::Running)),
+            );
+    }
+}
+
+impl DefinitionAsset for WaveSchedule {
+    const EXTENSIONS: &'static [&'static str] = &["waves.ron"];
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl WaveSchedule {
+    pub fn wave(&self, wave: u32) -> Option<(&WaveDef, i32)> {
+        let index = wave.saturating_sub(1) as usize;
+        match self.waves.get(index) {
+            Some(def) => Some((def, 0)),
+            None => self
+                .waves
+                .last()
+                .map(|def| (def, (index + 1 - self.waves.len()) as i32)),
+        }
+    }
+}
+
+impl WaveDirector {
+    fn start_wave(&mut self, wave: u32, schedules: &Assets<WaveSchedule>) {
+        let duration = self
+            .schedule
+            .as_ref()
+            .and_then(|schedule| schedules.get(schedule))
+            .and_then(|schedule| schedule.wave(wave))
+            .map_or(0.0, |(def, _)| def.duration);
+
+        self.wave = wave;
+        self.phase = WavePhase::Active;
+        self.timer = Timer::from_seconds(duration, TimerMode::Once);
+        self.pending = 0.0;
+        self.boss_pending = true;
+    }
+}
+
+fn reset_wave_director(
+    mut director: ResMut<WaveDirector>,
+    wave_library: Res<WaveLibrary>,
+    schedules: Res<Assets<WaveSchedule>>,
+) {
+    director.schedule = wave_library.get(WAVE_SCHEDULE, &schedules);
+    if director.schedule.is_none() {
+        warn!("Wave schedule \"{WAVE_SCHEDULE}\" was not found in \"{WAVES_PATH}\"");
+    }
+    director.start_wave(1, &schedules);
+}
+
+fn update_wave_director(
+    time: Res<Time>,
+    mut director: ResMut<WaveDirector>,
+    schedules: Res<Assets<WaveSchedule>>,
+) {
+    let Some(schedule) = director
+        .schedule
+        .as_ref()
+        .and_then(|schedule| schedules.get(schedule))
+    else {
+        return;
+    };
+    let Some((wave, escalation_steps)) = schedule.wave(director.wave) else {
+        return;
+    };
+
+    if director.phase == WavePhase::Active {
+        let spawn_rate = wave
+            .spawn_rate
+            .start
+            .lerp(wave.spawn_rate.end, director.timer.fraction())
+            * schedule.escalation.spawn_rate.powi(escalation_steps);
+        director.pending += spawn_rate * time.delta_seconds();
+    }
+
+    if !director.timer.tick(time.delta()).finished() {
+        return;
+    }
+
+    let next_wave = director.wave + 1;
+    match director.phase {
+        WavePhase::Active if wave.break_duration > 0.0 => {
+            director.phase = WavePhase::Break;
+            director.timer = Timer::from_seconds(wave.break_duration, TimerMode::Once);
+        }
+        _ => director.start_wave(next_wave, &schedules),
+    }
+}
+
+fn spawn_wave_enemies(
+    mut commands: Commands,
+    handle: Res<GlobalTextureAtlas>,
+    mut director: ResMut<WaveDirector>,
+    schedules: Res<Assets<WaveSchedule>>,
+    archetypes: EnemyArchetypes,
+    player_query: Query<&Transform, With<Player>>,
+    enemy_query: Query<(), With<Enemy>>,
+) {
+    if player_query.is_empty() || director.phase != WavePhase::Active {
+        return;
+    }
+    let Some(schedule) = director
+        .schedule
+        .as_ref()
+        .and_then(|schedule| schedules.get(schedule))
+    else {
+        return;
+    };
+    let Some((wave, escalation_steps)) = schedule.wave(director.wave) else {
+        return;
+    };
+
+    let escalation = schedule.escalation;
+    let health_multiplier = escalation.health.powi(escalation_steps);
+    let max_enemies = ((wave.max_enemies as f32 * escalation.max_enemies.powi(escalation_steps))
+        as usize)
+        .min(MAX_NUMBER_ENEMY);
+
+    let num_enemies = enemy_query.iter().len();
+    let enemy_spawn_count =
+        (director.pending as usize).min(max_enemies.saturating_sub(num_enemies));
+    director.pending = director.pending.fract();
+    if enemy_spawn_count == 0 {
+        return;
+    }
+
+    let archetypes: Vec<(Handle<EnemyDef>, &EnemyDef)> = archetypes.iter().collect();
+    let weights = archetypes.iter().map(|(_, def)| {
+        if def.boss.is_some() {
+            0.0
+        } else if wave.mix.is_empty() {
+            def.spawn_weight
+        } else {
+            wave.mix.get(&def.name).copied().unwrap_or(0.0)
+        }
+    });
+    let weights = match WeightedIndex::new(weights) {
+        Ok(weights) => weights,
+        Err(_) => {
+            warn_once!(
+                "Wave {} has no archetype to spawn in its mix, falling back to uniform weights",
+                director.wave
+            );
+            let uniform = archetypes
+                .iter()
+                .map(|(_, def)| if def.boss.is_some() { 0.0 } else { 1.0 });
+            let Ok(weights) = WeightedIndex::new(uniform) else {
+                return;
+            };
+            weights
+        }
+    };
+
+    let player_pos: Vec2 = player_query.single().translation.truncate();
+    let mut rng: ThreadRng = rand::thread_rng();
+    for _ in 0..enemy_spawn_count {
+        let (archetype, def) = &archetypes[weights.sample(&mut rng)];
+        let mut enemy = Enemy::new(archetype.clone(), def);
+        enemy.health *= health_multiplier;
+        spawn_enemy(
+            &mut commands,
+            &handle,
+            def,
+            enemy,
+            get_random_position_around(player_pos),
+        );
+    }
+}
+
+fn spawn_wave_boss(
+    mut commands: Commands,
+    handle: Res<GlobalTextureAtlas>,
+    mut director: ResMut<WaveDirector>,
+    schedules: Res<Assets<WaveSchedule>>,
+    archetypes: EnemyArchetypes,
+    player_query: Query<&Transform, With<Player>>,
+) {
+    if player_query.is_empty() || !director.boss_pending {
+        return;
+    }
+    let Some(schedule) = director
+        .schedule
+        .as_ref()
+        .and_then(|schedule| schedules.get(schedule))
+    else {
+        return;
+    };
+    let Some((wave, escalation_steps)) = schedule.wave(director.wave) else {
+        return;
+    };
+    let health_multiplier = schedule.escalation.health.powi(escalation_steps);
+    let boss_name = wave.boss.clone();
+    director.boss_pending = false;
+
+    let Some(boss_name) = boss_name else {
+        return;
+    };
+    let Some((archetype, def, boss_def)) = archetypes
+        .get(&boss_name)
+        .and_then(|(archetype, def)| def.boss.as_ref().map(|boss_def| (archetype, def, boss_def)))
+    else {
+        warn!("Boss \"{boss_name}\" was not found in \"{ENEMIES_PATH}\"");
+        return;
+    };
+
+    let player_pos: Vec2 = player_query.single().translation.truncate();
+    let angle = rand::thread_rng().gen_range(0.0..PI * 2.0);
+    let mut enemy = Enemy::new(archetype, def);
+    enemy.health *= health_multiplier;
+    spawn_boss(
+        &mut commands,
+        &handle,
+        def,
+        boss_def,
+        enemy,
+        player_pos + Vec2::from_angle(angle) * BOSS_SPAWN_DISTANCE,
+    );
+}
+
+fn get_random_position_around(pos: Vec2) -> Vec2 {
+    let mut rng: ThreadRng = rand::thread_rng();
+    let angle = rng.gen_range(0.0..PI * 2.0);
+    let dist = rng.gen_range(2048.0..4096.0);
+
+    pos + Vec2::new(dist * angle.cos(), dist * angle.sin())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule() -> WaveSchedule {
+        WaveSchedule {
+            waves: vec![
+                WaveDef {
+                    max_enemies: 10,
+                    ..default()
+                },
+                WaveDef {
+                    max_enemies: 20,
+                    ..default()
+                },
+            ],
+            ..default()
+        }
+    }
+
+    #[test]
+    fn scripted_waves_are_not_escalated() {
+        let schedule = schedule();
+        for (index, def) in schedule.waves.iter().enumerate() {
+            let (wave, escalation_steps) = schedule.wave(index as u32 + 1).unwrap();
+            assert_eq!(wave.max_enemies, def.max_enemies);
+            assert_eq!(escalation_steps, 0);
+        }
+    }
+
+    #[test]
+    fn last_wave_repeats_with_one_more_escalation_step_each_time() {
+        let schedule = schedule();
+        let scripted = schedule.waves.len() as u32;
+        let last = schedule.waves.last().unwrap();
+        for extra in 1..=3 {
+            let (wave, escalation_steps) = schedule.wave(scripted + extra).unwrap();
+            assert_eq!(wave.max_enemies, last.max_enemies);
+            assert_eq!(escalation_steps, extra as i32);
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::{DefinitionAsset, RonAssetLoader};
+
+pub struct WeaponPlugin;
+
+#[derive(Asset, TypePath, Deserialize, Debug, Clone, Default)]
+pub struct WeaponDef {
+    pub name: String,
+    pub fire_interval: f32,
+    pub spread: SpreadPattern,
+    pub pellets: usize,
+    pub projectile_speed: f32,
+    pub lifetime: f32,
+    pub max_range: f32,
+    pub damage: f32,
+    #[serde(default)]
+    pub pierce: u32,
+    #[serde(default)]
+    pub bounces: u32,
+    #[serde(default)]
+    pub explosion: Option<ExplosionDef>,
+    #[serde(default)]
+    pub homing: Option<HomingDef>,
+    pub magazine_size: u32,
+    pub max_reserve_ammo: u32,
+    pub reload_time: f32,
+    pub sprite_index: usize,
+    pub projectile_sprite_index: usize,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct ExplosionDef {
+    pub radius: f32,
+    pub damage: f32,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct HomingDef {
+    pub turn_rate: f32,
+    pub acquisition_radius: f32,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum SpreadPattern {
+    Fan { angle: f32 },
+    Cone { angle: f32 },
+    Gaussian { std_dev: f32 },
+}
+
+#[derive(Component)]
+pub struct Weapon(pub Handle<WeaponDef>);
+
+impl Plugin for WeaponPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<WeaponDef>()
+            .init_asset_loader::<RonAssetLoader<WeaponDef>>();
+    }
+}
+
+impl Default for SpreadPattern {
+    fn default() -> Self {
+        SpreadPattern::Fan { angle: 0.0 }
+    }
+}
+
+impl SpreadPattern {
+    pub fn angles(&self, pellets: usize, rng: &mut impl Rng) -> Vec<f32> {
+        match *self {
+            SpreadPattern::Fan { angle } => {
+                if pellets <= 1 {
+                    return vec![0.0; pellets];
+                }
+                let angle = angle.to_radians();
+                let step = angle / (pellets - 1) as f32;
+                (0..pellets)
+                    .map(|i| -angle / 2.0 + step * i as f32)
+                    .collect()
+            }
+            SpreadPattern::Cone { angle } => {
+                // A negative angle would give an empty range, which `gen_range` panics on.
+                let half_angle = angle.abs().to_radians() / 2.0;
+                (0..pellets)
+                    .map(|_| rng.gen_range(-half_angle..=half_angle))
+                    .collect()
+            }
+            SpreadPattern::Gaussian { std_dev } => {
+                let std_dev = std_dev.to_radians();
+                (0..pellets)
+                    .map(|_| {
+                        // Box-Muller transform, `u1` is kept away from zero so `ln` stays finite.
+                        let u1: f32 = 1.0 - rng.gen::<f32>();
+                        let u2: f32 = rng.gen();
+                        (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos() * std_dev
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+impl DefinitionAsset for WeaponDef {
+    const EXTENSIONS: &'static [&'static str] = &["weapon.ron"];
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn fan_spreads_pellets_evenly_across_the_angle() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let angles = SpreadPattern::Fan { angle: 30.0 }.angles(5, &mut rng);
+
+        assert_eq!(angles.len(), 5);
+        assert!((angles[0] + 15f32.to_radians()).abs() < 1e-6);
+        assert!((angles[4] - 15f32.to_radians()).abs() < 1e-6);
+        for pair in angles.windows(2) {
+            assert!((pair[1] - pair[0] - 7.5f32.to_radians()).abs() < 1e-6);
+        }
+        for (left, right) in angles.iter().zip(angles.iter().rev()) {
+            assert!((left + right).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn fan_with_a_single_pellet_fires_straight() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        assert_eq!(
+            SpreadPattern::Fan { angle: 30.0 }.angles(1, &mut rng),
+            [0.0]
+        );
+    }
+
+    #[test]
+    fn cone_stays_inside_the_angle() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let half_angle = 20f32.to_radians();
+
+        for angle in [40.0, -40.0] {
+            let angles = SpreadPattern::Cone { angle }.angles(1000, &mut rng);
+            assert_eq!(angles.len(), 1000);
+            assert!(angles.iter().all(|a| a.abs() <= half_angle));
+        }
+    }
+
+    #[test]
+    fn gaussian_is_centred_on_the_aim_direction() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let angles = SpreadPattern::Gaussian { std_dev: 10.0 }.angles(10_000, &mut rng);
+
+        let mean = angles.iter().sum::<f32>() / angles.len() as f32;
+        assert!(mean.abs() < 0.5f32.to_radians());
+    }
+}
@@ -1,15 +1,21 @@
 use animation::AnimationTimer;
-use bevy::{math::vec3, prelude::*, time::Stopwatch};
+use bevy::{math::vec3, prelude::*};
+use dash::Dash;
+use experience::Experience;
+use pickup::Buffs;
 use player::{Health, PlayerState};
 use rand::rngs::ThreadRng;
 use rand::Rng;
+use stats::{Stat, Stats};
 
 use crate::*;
 use crate::{
-    gun::{Gun, GunTimer},
+    gun::{spawn_gun, WeaponInventory},
+    passive::{DamageAura, OrbitingBlades},
     player::Player,
-    state::GameState,
-    GlobalTextureAtlas,
+    state::{GameState, InRun},
+    weapon::WeaponDef,
+    GlobalTextureAtlas, WeaponLibrary,
 };
 
 pub struct WorldPlugin;
@@ -23,7 +29,7 @@ impl Plugin for WorldPlugin {
             OnEnter(GameState::GameInit),
             (init_world, spawn_world_decorations).run_if(in_state(GameState::GameInit)),
         )
-        .add_systems(OnExit(GameState::InGame), despawn_all_games_entities);
+        .add_systems(OnExit(InRun), despawn_all_games_entities);
     }
 }
 
@@ -39,8 +45,22 @@ fn despawn_all_games_entities(
 fn init_world(
     mut commands: Commands,
     handle: Res<GlobalTextureAtlas>,
+    weapon_library: Res<WeaponLibrary>,
+    weapon_defs: Res<Assets<WeaponDef>>,
     mut next_state: ResMut<NextState<GameState>>,
 ) {
+    let mut guns = Vec::new();
+    for name in STARTING_WEAPONS {
+        match weapon_library.get(name, &weapon_defs) {
+            Some(weapon) => {
+                let weapon_def = weapon_defs.get(&weapon).unwrap();
+                guns.push(spawn_gun(&mut commands, &handle, weapon, weapon_def));
+            }
+            None => warn!("Starting weapon \"{name}\" was not found in \"{WEAPONS_PATH}\""),
+        }
+    }
+
+    let stats = Stats::default();
     commands.spawn((
         SpriteBundle {
             transform: Transform::from_translation(vec3(0.0, 0.0, 10.0))
@@ -54,23 +74,18 @@ fn init_world(
         },
         AnimationTimer(Timer::from_seconds(0.1, TimerMode::Repeating)),
         Player,
-        Health(PLAYER_HEALTH),
+        Health(stats.get(Stat::MaxHealth)),
         PlayerState::default(),
-        GameEntity,
-    ));
-    commands.spawn((
-        SpriteBundle {
-            transform: Transform::from_translation(vec3(0.0, 0.0, 12.0))
-                .with_scale(Vec3::splat(SPRITE_SCALE_FACTOR)),
-            texture: handle.image.clone().unwrap(),
-            ..default()
-        },
-        TextureAtlas {
-            layout: handle.layout.clone().unwrap(),
-            index: 17,
+        WeaponInventory {
+            slots: guns,
+            active: 0,
         },
-        Gun,
-        GunTimer(Stopwatch::new()),
+        OrbitingBlades::default(),
+        DamageAura::default(),
+        Experience::default(),
+        Buffs::default(),
+        stats,
+        Dash::default(),
         GameEntity,
     ));
 